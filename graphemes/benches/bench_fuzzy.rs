@@ -0,0 +1,54 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Run w/ `cargo bench`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use graphemes::fuzzy;
+
+const COMMANDS: &[&str] = &[
+  "git commit --patch",
+  "git checkout -b",
+  "cargo test --workspace",
+  "cargo clippy --all-targets",
+  "Open File",
+  "Toggle Performance HUD",
+  "Hi 😃 📦 🙏🏽 👨🏾‍🤝‍👨🏿.",
+];
+
+fn bench_fuzzy_score(c: &mut Criterion) {
+  c.bench_function("fuzzy::score short pattern over commands", |b| {
+    b.iter(|| {
+      COMMANDS
+        .iter()
+        .filter_map(|it| fuzzy::score(black_box("gcp"), it))
+        .count()
+    })
+  });
+
+  let long_line = "fn main() { let args = std::env::args().collect::<Vec<String>>(); }".repeat(4);
+  c.bench_function("fuzzy::score long line", |b| {
+    b.iter(|| fuzzy::score(black_box("collectstring"), black_box(&long_line)))
+  });
+
+  c.bench_function("fuzzy::score no match", |b| {
+    b.iter(|| fuzzy::score(black_box("xyzzy"), black_box(&long_line)))
+  });
+}
+
+criterion_group!(benches, bench_fuzzy_score);
+criterion_main!(benches);
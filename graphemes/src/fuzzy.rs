@@ -0,0 +1,159 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Fuzzy (subsequence) matching, eg: `"gcp"` matches `"git commit --patch"`. It is meant
+//! to be shared by anything that filters a list as the user types (eg: a command palette).
+//!
+//! Matching is done on grapheme clusters, not on `char`s, so a pattern never matches part
+//! of a cluster (eg: `"🙏"` doesn't match `"🙏🏽"`). It is also smart about case: the
+//! match is case insensitive, unless the pattern has an uppercase letter in it.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+const SCORE_MATCH: i64 = 16;
+/// For each grapheme cluster that is matched right after the previous one. It is bigger
+/// than the boundary bonuses, so that `"abc"` ranks `"abc"` above `"a_b_c"`.
+const BONUS_CONSECUTIVE: i64 = 10;
+const BONUS_FIRST: i64 = 12;
+/// After whitespace or a separator, eg: the `b` in `"foo_bar"` or `"foo/bar"`.
+const BONUS_BOUNDARY: i64 = 8;
+/// An uppercase letter after a lowercase one, eg: the `B` in `"fooBar"`.
+const BONUS_CAMEL_CASE: i64 = 7;
+/// For each grapheme cluster that is skipped between 2 matches.
+const PENALTY_GAP: i64 = 2;
+
+const SEPARATORS: &[&str] = &["_", "-", "/", "\\", ".", ":", ","];
+
+/// Returns `None` if `pattern` isn't a subsequence of `candidate`. Otherwise returns the
+/// score (higher is better, only useful for comparing candidates against the same pattern)
+/// & the indices of the matched grapheme clusters in `candidate` (eg: for highlighting).
+///
+/// The indices are grapheme cluster indices, not byte indices, which is what
+/// `UnicodeString` uses for its logical indices. An empty pattern matches everything, w/
+/// a score of `0`.
+///
+/// When there's more than one way to match, the one w/ the highest score is returned, eg:
+/// `"fb"` in `"fxxb foo_bar"` matches `[5, 9]` (both on word boundaries) instead of `[0, 3]`.
+pub fn score(
+  pattern: &str,
+  candidate: &str,
+) -> Option<(i64, Vec<usize>)> {
+  let is_case_sensitive = pattern.chars().any(char::is_uppercase);
+  let fold = |grapheme: &str| match is_case_sensitive {
+    true => grapheme.to_string(),
+    false => grapheme.to_lowercase(),
+  };
+
+  let pattern_vec: Vec<String> = pattern.graphemes(true).map(fold).collect();
+  let candidate_vec: Vec<&str> = candidate.graphemes(true).collect();
+  if pattern_vec.is_empty() {
+    return Some((0, vec![]));
+  }
+  if pattern_vec.len() > candidate_vec.len() {
+    return None;
+  }
+  let folded_candidate_vec: Vec<String> = candidate_vec
+    .iter()
+    .map(|it| fold(it))
+    .collect();
+  let bonus_vec: Vec<i64> = (0..candidate_vec.len())
+    .map(|index| get_bonus(&candidate_vec, index))
+    .collect();
+
+  // `score_table[i][j]` is the best score for matching `pattern_vec[..=i]`, w/
+  // `pattern_vec[i]` matched at `candidate_vec[j]` (or `None` if that can't be done). And
+  // `parent_table[i][j]` is where `pattern_vec[i - 1]` was matched, to get that score.
+  let (pattern_len, candidate_len) = (pattern_vec.len(), candidate_vec.len());
+  let mut score_table: Vec<Vec<Option<i64>>> = vec![vec![None; candidate_len]; pattern_len];
+  let mut parent_table: Vec<Vec<usize>> = vec![vec![0; candidate_len]; pattern_len];
+
+  for j in 0..candidate_len {
+    if pattern_vec[0] == folded_candidate_vec[j] {
+      score_table[0][j] = Some(SCORE_MATCH + bonus_vec[j]);
+    }
+  }
+
+  for i in 1..pattern_len {
+    // Best (score - gap penalty, index) in the previous row, for the indices that are not
+    // right before `j`. Every step of `j` makes each of those gaps 1 longer.
+    let mut best_gapped: Option<(i64, usize)> = None;
+    for j in 1..candidate_len {
+      best_gapped = best_gapped.map(|(score, k)| (score - PENALTY_GAP, k));
+      if j >= 2 {
+        if let Some(score) = score_table[i - 1][j - 2] {
+          let score = score - PENALTY_GAP;
+          if !matches!(best_gapped, Some((best_score, _)) if best_score >= score) {
+            best_gapped = Some((score, j - 2));
+          }
+        }
+      }
+
+      if pattern_vec[i] != folded_candidate_vec[j] {
+        continue;
+      }
+
+      let consecutive =
+        score_table[i - 1][j - 1].map(|score| (score + BONUS_CONSECUTIVE, j - 1));
+      let best = match (consecutive, best_gapped) {
+        (Some(consecutive), Some(gapped)) if gapped.0 > consecutive.0 => Some(gapped),
+        (Some(consecutive), _) => Some(consecutive),
+        (None, gapped) => gapped,
+      };
+      if let Some((score, k)) = best {
+        score_table[i][j] = Some(score + SCORE_MATCH + bonus_vec[j]);
+        parent_table[i][j] = k;
+      }
+    }
+  }
+
+  // The earliest of the best matches for the last grapheme cluster of the pattern.
+  let (mut j, best_score) = score_table[pattern_len - 1]
+    .iter()
+    .enumerate()
+    .filter_map(|(j, score)| score.map(|score| (j, score)))
+    .fold(None, |best: Option<(usize, i64)>, (j, score)| match best {
+      Some((_, best_score)) if best_score >= score => best,
+      _ => Some((j, score)),
+    })?;
+
+  let mut match_indices = vec![0; pattern_len];
+  for i in (0..pattern_len).rev() {
+    match_indices[i] = j;
+    j = parent_table[i][j];
+  }
+
+  Some((best_score, match_indices))
+}
+
+fn get_bonus(
+  candidate_vec: &[&str],
+  index: usize,
+) -> i64 {
+  if index == 0 {
+    return BONUS_FIRST;
+  }
+  let (prev, current) = (candidate_vec[index - 1], candidate_vec[index]);
+  let is_lowercase = |it: &str| it.chars().all(char::is_lowercase);
+  let is_uppercase = |it: &str| it.chars().all(char::is_uppercase);
+  if prev.chars().all(char::is_whitespace) || SEPARATORS.contains(&prev) {
+    BONUS_BOUNDARY
+  } else if is_lowercase(prev) && is_uppercase(current) {
+    BONUS_CAMEL_CASE
+  } else {
+    0
+  }
+}
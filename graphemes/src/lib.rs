@@ -15,5 +15,10 @@
  *   limitations under the License.
  */
 
+pub mod fuzzy;
+pub mod term_caps;
+pub mod terminal_session;
 pub mod unicode_string_ext;
+pub use term_caps::*;
+pub use terminal_session::*;
 pub use unicode_string_ext::*;
@@ -32,11 +32,13 @@ use crossterm::{cursor::{self, *},
                 style::*,
                 terminal::{self, *},
                 Result};
+use graphemes::{TermCaps, TerminalSession};
 use seshat::unicode::{Segmentation, Ucd};
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
 fn main() -> Result<()> {
+  print_term_caps();
   print_graphemes();
   print_cluster_breaks_using_seshat_and_unicode_width();
   print_graphemes_using_unicode_segmentation_and_unicode_width();
@@ -45,14 +47,18 @@ fn main() -> Result<()> {
   Ok(())
 }
 
+/// Run w/ eg: `TERM_CAPS=no-zwj-clusters cargo run` to override what is detected.
+pub fn print_term_caps() {
+  println!("{:#?}", TermCaps::detect());
+}
+
 const TEST_STRING: &str = "Hi 😃 📦 🙏🏽 👨🏾‍🤝‍👨🏿.";
 
 pub fn test_crossterm_grapheme_cluster_width_calc() -> Result<()> {
-  // Enter raw mode, clear screen.
-  enable_raw_mode()?;
-  execute!(stdout(), EnterAlternateScreen)?;
-  execute!(stdout(), Clear(ClearType::All))?;
-  execute!(stdout(), MoveTo(0, 0))?;
+  // Enter raw mode, clear screen. The terminal is restored when `session` is dropped, even if
+  // one of the commands below fails or panics.
+  let session = TerminalSession::start()?;
+  session.draw_frame(|stdout| execute!(stdout, Clear(ClearType::All), MoveTo(0, 0)))?;
 
   // Perform test of grapheme cluster width.
   #[derive(Default, Debug, Clone, Copy)]
@@ -86,20 +92,33 @@ pub fn test_crossterm_grapheme_cluster_width_calc() -> Result<()> {
 
   process_map(&mut map)?;
 
-  // Just blocking on user input.
-  {
+  // Just blocking on user input. Ctrl+Z suspends the program, which repaints once it is
+  // resumed w/ `fg`.
+  loop {
     execute!(stdout(), Print("... Press any key to continue ..."))?;
-    if let Event::Key(_) = read()? {
-      execute!(stdout(), terminal::Clear(ClearType::All))?;
-      execute!(stdout(), cursor::MoveTo(0, 0))?;
+    match read()? {
+      Event::Key(key)
+        if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) =>
+      {
+        session.suspend()?;
+        if session.take_resumed_size()?.is_some() {
+          execute!(stdout(), terminal::Clear(ClearType::All))?;
+          process_map(&mut map)?;
+        }
+      }
+      Event::Key(_) => {
+        execute!(stdout(), terminal::Clear(ClearType::All))?;
+        execute!(stdout(), cursor::MoveTo(0, 0))?;
+        break;
+      }
+      _ => break,
     }
   }
 
   // Exit raw mode, clear screen.
   execute!(stdout(), terminal::Clear(ClearType::All))?;
   execute!(stdout(), cursor::MoveTo(0, 0))?;
-  execute!(stdout(), LeaveAlternateScreen)?;
-  disable_raw_mode().expect("Unable to disable raw mode");
+  drop(session);
   println!("map:{:#?}", map);
 
   Ok(())
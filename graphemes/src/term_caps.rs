@@ -0,0 +1,121 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::env;
+
+/// Env var that overrides the detected capabilities. It is a comma separated list of
+/// capability names, each of which can be prefixed w/ `no-` to turn it off, eg:
+/// `TERM_CAPS=truecolor,no-italics`. Unknown names are ignored.
+///
+/// The names are: `truecolor`, `256color`, `italics`, `kitty-keyboard`, `sync-output`,
+/// `zwj-clusters`.
+pub const TERM_CAPS_ENV_VAR: &str = "TERM_CAPS";
+
+/// What the terminal (probably) supports. Querying the terminal for most of these requires
+/// writing escape sequences & reading back the replies in raw mode, so they are guessed
+/// from the `TERM`, `TERM_PROGRAM` & `COLORTERM` env vars instead. When a guess is wrong,
+/// [TERM_CAPS_ENV_VAR] can be used to fix it (it is also handy in tests).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TermCaps {
+  /// 24 bit RGB colors.
+  pub truecolor: bool,
+  /// The 256 color palette. Always true if [TermCaps::truecolor] is.
+  pub color_256: bool,
+  pub italics: bool,
+  /// <https://sw.kovidgoyal.net/kitty/keyboard-protocol/>
+  pub kitty_keyboard: bool,
+  /// DEC mode 2026, which lets a whole frame be drawn at once (w/out tearing).
+  pub synchronized_output: bool,
+  /// Whether emoji ZWJ sequences (eg: "👨🏾‍🤝‍👨🏿") are drawn as a single (2 column wide)
+  /// grapheme cluster, instead of as each of the emoji that they are made of. Terminals
+  /// that don't do this make the display width of the cluster differ from what
+  /// `unicode-width` reports.
+  pub zwj_clusters: bool,
+}
+
+impl TermCaps {
+  /// Guesses the capabilities from the env vars of this process, then applies the
+  /// [TERM_CAPS_ENV_VAR] overrides.
+  pub fn detect() -> Self { Self::from_env_vars(|name| env::var(name).ok()) }
+
+  /// Same as [TermCaps::detect], but the env vars are looked up using `get_var`.
+  pub fn from_env_vars(get_var: impl Fn(&str) -> Option<String>) -> Self {
+    let term = get_var("TERM").unwrap_or_default();
+    let term_program = get_var("TERM_PROGRAM").unwrap_or_default();
+    let colorterm = get_var("COLORTERM").unwrap_or_default();
+
+    let is_term = |names: &[&str]| names.iter().any(|it| term.starts_with(it));
+    let is_term_program = |names: &[&str]| names.contains(&term_program.as_str());
+
+    let is_dumb = term.is_empty() || term == "dumb";
+
+    let truecolor = !is_dumb
+      && (colorterm == "truecolor"
+        || colorterm == "24bit"
+        || term.ends_with("-direct")
+        || is_term(&["xterm-kitty", "wezterm", "alacritty", "foot", "xterm-ghostty"])
+        || is_term_program(&["iTerm.app", "WezTerm", "vscode", "ghostty"]));
+
+    let mut term_caps = TermCaps {
+      truecolor,
+      color_256: truecolor || term.contains("256color"),
+      italics: !is_dumb && !is_term(&["linux", "screen", "vt"]),
+      kitty_keyboard: is_term(&["xterm-kitty", "foot", "xterm-ghostty"])
+        || is_term_program(&["ghostty"]),
+      synchronized_output: is_term(&[
+        "xterm-kitty",
+        "wezterm",
+        "alacritty",
+        "foot",
+        "xterm-ghostty",
+        "contour",
+      ]) || is_term_program(&["iTerm.app", "WezTerm", "ghostty"]),
+      zwj_clusters: is_term(&["xterm-kitty", "wezterm", "foot", "xterm-ghostty"])
+        || is_term_program(&["iTerm.app", "WezTerm", "ghostty"]),
+    };
+
+    if let Some(overrides) = get_var(TERM_CAPS_ENV_VAR) {
+      term_caps.apply_overrides(&overrides);
+    }
+
+    term_caps
+  }
+
+  fn apply_overrides(
+    &mut self,
+    overrides: &str,
+  ) {
+    for name in overrides.split(',').map(str::trim) {
+      let (name, value) = match name.strip_prefix("no-") {
+        Some(name) => (name, false),
+        None => (name, true),
+      };
+      match name {
+        "truecolor" => self.truecolor = value,
+        "256color" => self.color_256 = value,
+        "italics" => self.italics = value,
+        "kitty-keyboard" => self.kitty_keyboard = value,
+        "sync-output" => self.synchronized_output = value,
+        "zwj-clusters" => self.zwj_clusters = value,
+        _ => {}
+      }
+    }
+    if self.truecolor {
+      self.color_256 = true;
+    }
+  }
+}
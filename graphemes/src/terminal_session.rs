@@ -0,0 +1,205 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::{io::{stdout, Stdout, Write},
+          panic,
+          sync::{atomic::{AtomicBool, Ordering},
+                 Once},
+          thread};
+
+use crossterm::{execute,
+                terminal::{self, disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+                Result};
+use signal_hook::{consts::{SIGCONT, SIGTSTP},
+                  iterator::Signals,
+                  low_level::emulate_default_handler};
+
+use crate::TermCaps;
+
+static INSTALL_PANIC_HOOK: Once = Once::new();
+static INSTALL_SIGNAL_HANDLER: Once = Once::new();
+static IS_ACTIVE: AtomicBool = AtomicBool::new(false);
+static IS_SUSPENDED: AtomicBool = AtomicBool::new(false);
+static IS_RESUMED: AtomicBool = AtomicBool::new(false);
+
+/// DEC mode 2026: <https://gist.github.com/christianparpart/d8a62cc1ab659194337d73e399004036>
+const BEGIN_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026h";
+const END_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026l";
+
+/// RAII guard that puts the terminal in raw mode & switches to the alternate screen. The
+/// terminal is restored when this is dropped, and also if the program panics while it is
+/// active (otherwise the panic message is lost in the alternate screen and the user's shell
+/// is left in raw mode).
+///
+/// ```ignore
+/// fn main() -> Result<()> {
+///   let _session = TerminalSession::start()?;
+///   // Draw to the alternate screen in raw mode ...
+///   Ok(())
+/// } // `_session` dropped here, terminal restored.
+/// ```
+///
+/// The program can be suspended (& resumed w/ `fg`), see [TerminalSession::suspend].
+#[derive(Debug)]
+pub struct TerminalSession {
+  term_caps: TermCaps,
+}
+
+impl TerminalSession {
+  pub fn start() -> Result<Self> {
+    install_panic_hook();
+    install_signal_handler();
+    enable_raw_mode()?;
+    IS_ACTIVE.store(true, Ordering::SeqCst);
+    // Create the session before entering the alternate screen, so that raw mode is still
+    // disabled (by `drop()`) if the following command fails.
+    let session = TerminalSession {
+      term_caps: TermCaps::detect(),
+    };
+    execute!(stdout(), EnterAlternateScreen)?;
+    Ok(session)
+  }
+
+  /// Detected when the session is started.
+  pub fn term_caps(&self) -> &TermCaps { &self.term_caps }
+
+  /// Draws a whole frame to `stdout`, see [draw_frame].
+  pub fn draw_frame<T>(
+    &self,
+    draw: impl FnOnce(&mut Stdout) -> Result<T>,
+  ) -> Result<T> {
+    draw_frame(&mut stdout(), &self.term_caps, draw)
+  }
+
+  /// Call this when the user presses Ctrl+Z. Raw mode turns off the terminal's own handling
+  /// of Ctrl+Z, so the program has to stop itself. The terminal is restored (so the shell
+  /// works normally), the program is stopped, & once it is continued (eg: via `fg`), raw
+  /// mode & the alternate screen are turned back on.
+  ///
+  /// A `SIGTSTP` sent by another process (eg: `kill -TSTP <pid>`) is handled the same way.
+  /// After either, [TerminalSession::take_resumed_size] returns the new terminal size.
+  pub fn suspend(&self) -> Result<()> { suspend_terminal() }
+
+  /// Returns the terminal size if the program was continued (after being stopped) since the
+  /// last call. The alternate screen is blank at that point, & the terminal might have been
+  /// resized in the meantime, so the whole screen has to be repainted using this size.
+  pub fn take_resumed_size(&self) -> Result<Option<(u16, u16)>> {
+    match IS_RESUMED.swap(false, Ordering::SeqCst) {
+      true => Ok(Some(terminal::size()?)),
+      false => Ok(None),
+    }
+  }
+}
+
+impl Drop for TerminalSession {
+  fn drop(&mut self) { restore_terminal(); }
+}
+
+/// Draws a whole frame to `writer` using `draw`, then flushes it. If the terminal supports
+/// synchronized output, the frame is wrapped in begin & end synchronized update sequences,
+/// so it shows up all at once instead of tearing. Otherwise it is drawn as is.
+///
+/// The end sequence is written even if `draw` fails, since the terminal stops updating the
+/// screen (until it times out) after the begin sequence.
+pub fn draw_frame<W: Write, T>(
+  writer: &mut W,
+  term_caps: &TermCaps,
+  draw: impl FnOnce(&mut W) -> Result<T>,
+) -> Result<T> {
+  if !term_caps.synchronized_output {
+    let it = draw(writer)?;
+    writer.flush()?;
+    return Ok(it);
+  }
+
+  write!(writer, "{}", BEGIN_SYNCHRONIZED_UPDATE)?;
+  let result = draw(writer);
+  write!(writer, "{}", END_SYNCHRONIZED_UPDATE)?;
+  writer.flush()?;
+  result
+}
+
+/// Errors are ignored, since this runs in `drop()` & in the panic hook, where there is no
+/// way to report them. It does nothing if there is no active session, so it is safe to call
+/// this more than once.
+fn restore_terminal() {
+  if IS_ACTIVE.swap(false, Ordering::SeqCst) && !IS_SUSPENDED.load(Ordering::SeqCst) {
+    let _ = execute!(stdout(), LeaveAlternateScreen);
+    let _ = disable_raw_mode();
+  }
+}
+
+/// Stops the program until it gets a `SIGCONT`. The default handler is emulated (instead of
+/// raising `SIGTSTP`), since [install_signal_handler] replaces the handler for `SIGTSTP`.
+fn suspend_terminal() -> Result<()> {
+  if IS_ACTIVE.load(Ordering::SeqCst) && !IS_SUSPENDED.swap(true, Ordering::SeqCst) {
+    execute!(stdout(), LeaveAlternateScreen)?;
+    disable_raw_mode()?;
+  }
+  emulate_default_handler(SIGTSTP)?;
+  resume_terminal()
+}
+
+/// Raw mode & the alternate screen are only turned back on if the terminal was suspended
+/// by [suspend_terminal], so it is safe to call this both after [suspend_terminal] returns
+/// & when `SIGCONT` arrives. The repaint is requested either way, since the program might
+/// also have been stopped by `SIGSTOP` (which can't be handled).
+fn resume_terminal() -> Result<()> {
+  if !IS_ACTIVE.load(Ordering::SeqCst) {
+    return Ok(());
+  }
+  if IS_SUSPENDED.swap(false, Ordering::SeqCst) {
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen)?;
+  }
+  IS_RESUMED.store(true, Ordering::SeqCst);
+  Ok(())
+}
+
+/// Handles `SIGTSTP` (from other processes) & `SIGCONT` on a background thread. If the
+/// handler can't be registered, then only [TerminalSession::suspend] works. Only installed
+/// once, no matter how many sessions are started.
+fn install_signal_handler() {
+  INSTALL_SIGNAL_HANDLER.call_once(|| {
+    let mut signals = match Signals::new([SIGTSTP, SIGCONT]) {
+      Ok(signals) => signals,
+      Err(_) => return,
+    };
+    thread::spawn(move || {
+      for signal in signals.forever() {
+        // Errors are ignored, since there is no way to report them from this thread.
+        let _ = match signal {
+          SIGTSTP => suspend_terminal(),
+          _ => resume_terminal(),
+        };
+      }
+    });
+  });
+}
+
+/// Restore the terminal before the default (or previously installed) panic hook prints the
+/// panic message, so that it shows up in the main screen. Only installed once, no matter
+/// how many sessions are started.
+fn install_panic_hook() {
+  INSTALL_PANIC_HOOK.call_once(|| {
+    let prev_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |panic_info| {
+      restore_terminal();
+      prev_hook(panic_info);
+    }));
+  });
+}
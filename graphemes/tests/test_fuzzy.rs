@@ -0,0 +1,72 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use graphemes::fuzzy;
+use r3bl_rs_utils::assert_eq2;
+
+fn match_indices(
+  pattern: &str,
+  candidate: &str,
+) -> Option<Vec<usize>> {
+  fuzzy::score(pattern, candidate).map(|(_, match_indices)| match_indices)
+}
+
+fn score(
+  pattern: &str,
+  candidate: &str,
+) -> i64 {
+  fuzzy::score(pattern, candidate).unwrap().0
+}
+
+#[test]
+fn test_fuzzy_match_indices() {
+  assert_eq2!(match_indices("", "abc"), Some(vec![]));
+  assert_eq2!(match_indices("gcp", "git commit --patch"), Some(vec![0, 4, 13]));
+  assert_eq2!(match_indices("fb", "foo_bar"), Some(vec![0, 4]));
+  assert_eq2!(match_indices("fb", "fxxb foo_bar"), Some(vec![5, 9]));
+  assert_eq2!(match_indices("abc", "ab"), None);
+  assert_eq2!(match_indices("xyz", "abc"), None);
+  assert_eq2!(match_indices("ba", "abc"), None);
+}
+
+#[test]
+fn test_fuzzy_smart_case() {
+  assert_eq2!(match_indices("fb", "FooBar"), Some(vec![0, 3]));
+  assert_eq2!(match_indices("FB", "FooBar"), Some(vec![0, 3]));
+  assert_eq2!(match_indices("FB", "foo_bar"), None);
+}
+
+#[test]
+fn test_fuzzy_grapheme_clusters() {
+  // The indices are grapheme cluster indices, not byte indices.
+  assert_eq2!(match_indices("🙏🏽t", "hi 🙏🏽 there"), Some(vec![3, 5]));
+  // Part of a grapheme cluster doesn't match.
+  assert_eq2!(match_indices("🙏", "🙏🏽"), None);
+  assert_eq2!(match_indices("e\u{301}", "cafe\u{301}"), Some(vec![3]));
+  assert_eq2!(match_indices("e", "cafe\u{301}"), None);
+}
+
+#[test]
+fn test_fuzzy_ranking() {
+  // Consecutive matches beat word boundaries, which beat scattered matches.
+  assert!(score("abc", "abc") > score("abc", "a_b_c"));
+  assert!(score("abc", "a_b_c") > score("abc", "axxbxxc"));
+  // Matches at the start beat matches in the middle.
+  assert!(score("gr", "grep") > score("gr", "agree"));
+  // Camel case humps count as boundaries.
+  assert!(score("fb", "fooBar") > score("fb", "foobar"));
+}
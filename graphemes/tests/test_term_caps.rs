@@ -0,0 +1,105 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use graphemes::{TermCaps, TERM_CAPS_ENV_VAR};
+use r3bl_rs_utils::assert_eq2;
+
+/// Detects the capabilities from the given env vars (& no others).
+fn from_env_vars(env_vars: &[(&str, &str)]) -> TermCaps {
+  TermCaps::from_env_vars(|name| {
+    env_vars
+      .iter()
+      .find(|(it, _)| *it == name)
+      .map(|(_, value)| value.to_string())
+  })
+}
+
+#[test]
+fn test_term_caps_dumb() {
+  assert_eq2!(from_env_vars(&[]), TermCaps::default());
+  assert_eq2!(from_env_vars(&[("TERM", "dumb")]), TermCaps::default());
+  // COLORTERM doesn't count for a dumb terminal.
+  assert_eq2!(
+    from_env_vars(&[("TERM", "dumb"), ("COLORTERM", "truecolor")]),
+    TermCaps::default()
+  );
+}
+
+#[test]
+fn test_term_caps_guessed_from_term() {
+  let term_caps = from_env_vars(&[("TERM", "xterm-256color")]);
+  assert!(!term_caps.truecolor);
+  assert!(term_caps.color_256);
+  assert!(term_caps.italics);
+  assert!(!term_caps.synchronized_output);
+
+  let term_caps = from_env_vars(&[("TERM", "linux")]);
+  assert!(!term_caps.color_256);
+  assert!(!term_caps.italics);
+
+  assert_eq2!(
+    from_env_vars(&[("TERM", "xterm-kitty")]),
+    TermCaps {
+      truecolor: true,
+      color_256: true,
+      italics: true,
+      kitty_keyboard: true,
+      synchronized_output: true,
+      zwj_clusters: true,
+    }
+  );
+}
+
+#[test]
+fn test_term_caps_truecolor_implies_256_colors() {
+  for env_vars in [
+    [("TERM", "xterm"), ("COLORTERM", "truecolor")],
+    [("TERM", "xterm"), ("COLORTERM", "24bit")],
+    [("TERM", "xterm-direct"), ("COLORTERM", "")],
+    [("TERM", "xterm"), ("TERM_PROGRAM", "iTerm.app")],
+  ] {
+    let term_caps = from_env_vars(&env_vars);
+    assert!(term_caps.truecolor, "{:?}", env_vars);
+    assert!(term_caps.color_256, "{:?}", env_vars);
+  }
+}
+
+#[test]
+fn test_term_caps_overrides() {
+  let term_caps = from_env_vars(&[
+    ("TERM", "xterm-kitty"),
+    (TERM_CAPS_ENV_VAR, "no-italics, no-kitty-keyboard,no-zwj-clusters"),
+  ]);
+  assert!(term_caps.truecolor);
+  assert!(!term_caps.italics);
+  assert!(!term_caps.kitty_keyboard);
+  assert!(!term_caps.zwj_clusters);
+
+  // Turning on truecolor turns on 256 colors too, & unknown names are ignored.
+  assert_eq2!(
+    from_env_vars(&[
+      ("TERM", "dumb"),
+      (TERM_CAPS_ENV_VAR, "truecolor,sync-output,blink,no-"),
+    ]),
+    TermCaps {
+      truecolor: true,
+      color_256: true,
+      synchronized_output: true,
+      ..TermCaps::default()
+    }
+  );
+}
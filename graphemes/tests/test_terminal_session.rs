@@ -0,0 +1,69 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use std::io::{Error, ErrorKind, Write};
+
+use graphemes::{draw_frame, TermCaps};
+use r3bl_rs_utils::assert_eq2;
+
+const BEGIN_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026h";
+const END_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026l";
+
+fn term_caps(synchronized_output: bool) -> TermCaps {
+  TermCaps {
+    synchronized_output,
+    ..TermCaps::default()
+  }
+}
+
+#[test]
+fn test_draw_frame_synchronized() {
+  let mut output: Vec<u8> = vec![];
+  let result = draw_frame(&mut output, &term_caps(true), |writer| {
+    write!(writer, "frame")?;
+    Ok(42)
+  });
+  assert_eq2!(result.unwrap(), 42);
+  assert_eq2!(
+    String::from_utf8(output).unwrap(),
+    format!("{}frame{}", BEGIN_SYNCHRONIZED_UPDATE, END_SYNCHRONIZED_UPDATE)
+  );
+}
+
+#[test]
+fn test_draw_frame_not_synchronized() {
+  let mut output: Vec<u8> = vec![];
+  draw_frame(&mut output, &term_caps(false), |writer| write!(writer, "frame")).unwrap();
+  assert_eq2!(String::from_utf8(output).unwrap(), "frame");
+}
+
+#[test]
+fn test_draw_frame_ends_update_when_draw_fails() {
+  let mut output: Vec<u8> = vec![];
+  let result = draw_frame(&mut output, &term_caps(true), |writer| {
+    write!(writer, "half a frame")?;
+    Err::<(), _>(Error::new(ErrorKind::Other, "draw failed"))
+  });
+  assert_eq2!(result.unwrap_err().to_string(), "draw failed");
+  assert_eq2!(
+    String::from_utf8(output).unwrap(),
+    format!(
+      "{}half a frame{}",
+      BEGIN_SYNCHRONIZED_UPDATE, END_SYNCHRONIZED_UPDATE
+    )
+  );
+}
@@ -0,0 +1,180 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parenthesized,
+          parse::{Parse, ParseStream},
+          parse_macro_input,
+          Error,
+          Expr,
+          Ident,
+          Result,
+          Token};
+
+/// See [`AssertLayoutInfo`] for more information on the syntax that this macro accepts.
+///
+/// Each expected field expands into its own `assert_eq!`, and the failure message names
+/// the layout & the field, followed by the `{:#?}` dump of the whole layout.
+pub fn fn_proc_macro_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+  let AssertLayoutInfo {
+    layout_expr,
+    expected_field_vec,
+  } = parse_macro_input!(input as AssertLayoutInfo);
+
+  // Mixed site, so that it doesn't shadow a `layout` that is used in an expected value.
+  let layout_ident = Ident::new("__assert_layout_target", Span::mixed_site());
+
+  let asserts_ts_iter = expected_field_vec
+    .iter()
+    .map(|expected_field| gen_assert_ts(&layout_ident, expected_field));
+
+  quote! {
+    {
+      let #layout_ident = &(#layout_expr);
+      #(#asserts_ts_iter)*
+    }
+  }
+  .into()
+}
+
+/// Returns [proc_macro2::TokenStream] (not [proc_macro::TokenStream]).
+///
+/// The layout is expected to implement `Debug` & to have these fields: `id`, `dir` (a
+/// `Direction`, resolved at the call site), `origin_pos` (w/ `x` & `y`) & `bounds_size` (w/
+/// `width` & `height`).
+fn gen_assert_ts(
+  layout_ident: &Ident,
+  expected_field: &ExpectedField,
+) -> proc_macro2::TokenStream {
+  let (field_name_str, actual_ts, expected_ts) = match expected_field {
+    ExpectedField::Id(id_expr) => ("id", quote! { #layout_ident.id }, quote! { #id_expr }),
+    ExpectedField::Dir(dir_ident) => (
+      "dir",
+      quote! { #layout_ident.dir },
+      quote! { Direction::#dir_ident },
+    ),
+    ExpectedField::Origin(x_expr, y_expr) => (
+      "origin",
+      quote! { (#layout_ident.origin_pos.x, #layout_ident.origin_pos.y) },
+      quote! { (#x_expr, #y_expr) },
+    ),
+    ExpectedField::Bounds(width_expr, height_expr) => (
+      "bounds",
+      quote! { (#layout_ident.bounds_size.width, #layout_ident.bounds_size.height) },
+      quote! { (#width_expr, #height_expr) },
+    ),
+  };
+
+  quote! {
+    assert_eq!(
+      #actual_ts,
+      #expected_ts,
+      "layout `{}` has unexpected `{}`, layout: {:#?}",
+      #layout_ident.id,
+      #field_name_str,
+      #layout_ident
+    );
+  }
+}
+
+/// Example of syntax to parse:
+/// ```ignore
+/// assert_layout! {
+///   layout,
+///   id: "col_2",
+///   origin: (250, 0),
+///   bounds: (250, 500),
+///   dir: Vertical,
+/// }
+/// ```
+///
+/// - `layout` is any expression that evaluates to a layout (it is borrowed, not moved).
+/// - The expected fields are all optional, and they are checked in the order given.
+struct AssertLayoutInfo {
+  layout_expr: Expr,
+  expected_field_vec: Vec<ExpectedField>,
+}
+
+enum ExpectedField {
+  Id(Expr),
+  Dir(Ident),
+  Origin(Expr, Expr),
+  Bounds(Expr, Expr),
+}
+
+/// [Parse docs](https://docs.rs/syn/latest/syn/parse/index.html)
+impl Parse for AssertLayoutInfo {
+  fn parse(input: ParseStream) -> Result<Self> {
+    // 👀 Layout, eg: `layout`.
+    let layout_expr: Expr = input.parse()?;
+
+    let mut expected_field_vec: Vec<ExpectedField> = Vec::new();
+    let mut key_vec: Vec<String> = Vec::new();
+
+    while !input.is_empty() {
+      input.parse::<Token![,]>()?;
+      if input.is_empty() {
+        break;
+      }
+
+      // 👀 Expected field, eg: `origin: (250, 0)`.
+      let key: Ident = input.parse()?;
+      input.parse::<Token![:]>()?;
+
+      let expected_field = match key.to_string().as_str() {
+        "id" => ExpectedField::Id(input.parse()?),
+        "dir" => ExpectedField::Dir(input.parse()?),
+        "origin" => {
+          let (x_expr, y_expr) = parse_pair(input)?;
+          ExpectedField::Origin(x_expr, y_expr)
+        }
+        "bounds" => {
+          let (width_expr, height_expr) = parse_pair(input)?;
+          ExpectedField::Bounds(width_expr, height_expr)
+        }
+        _ => {
+          return Err(Error::new(
+            key.span(),
+            format!("unknown key `{}`, expected one of: id, dir, origin, bounds", key),
+          ))
+        }
+      };
+
+      if key_vec.contains(&key.to_string()) {
+        return Err(Error::new(key.span(), format!("duplicate key `{}`", key)));
+      }
+      key_vec.push(key.to_string());
+      expected_field_vec.push(expected_field);
+    }
+
+    Ok(AssertLayoutInfo {
+      layout_expr,
+      expected_field_vec,
+    })
+  }
+}
+
+/// Parses a pair of expressions, eg: `(250, 0)`.
+fn parse_pair(input: ParseStream) -> Result<(Expr, Expr)> {
+  let content;
+  parenthesized!(content in input);
+  let first: Expr = content.parse()?;
+  content.parse::<Token![,]>()?;
+  let second: Expr = content.parse()?;
+  Ok((first, second))
+}
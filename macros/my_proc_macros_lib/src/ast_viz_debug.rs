@@ -15,30 +15,77 @@
  *   limitations under the License.
 */
 
+use std::fmt::{self, Display};
+
 use proc_macro::TokenStream;
-use quote::ToTokens;
+use quote::{quote, ToTokens};
 use r3bl_rs_utils::{style_primary, style_prompt};
-use syn::{parse_str, ItemFn};
+use syn::{parse::{Parse, ParseStream},
+          parse_macro_input,
+          Ident,
+          ItemFn,
+          Result,
+          ReturnType,
+          Token};
+
+mod kw {
+  syn::custom_keyword!(report);
+  syn::custom_keyword!(warning);
+}
 
+/// See [`AstVizDebugInfo`] for more information on the syntax that this macro accepts.
+///
+/// The function is passed through unchanged. Its AST is dumped to `stderr` at compile time
+/// and the optional report items are generated alongside it.
+///
 /// https://docs.rs/syn/latest/syn/macro.parse_macro_input.html
-pub fn fn_proc_macro_impl(_input: TokenStream) -> TokenStream {
-  let output_token_stream_str = "fn foo() -> u32 { 42 }";
-  let output = output_token_stream_str
-    .parse()
-    .unwrap();
+pub fn fn_proc_macro_impl(input: TokenStream) -> TokenStream {
+  let AstVizDebugInfo {
+    report_options,
+    item_fn,
+  } = parse_macro_input!(input as AstVizDebugInfo);
 
-  let ast_item_fn: ItemFn = parse_str::<ItemFn>(output_token_stream_str).unwrap();
+  let report = AstVizReport::from(&item_fn);
 
-  // viz_token_stream("input", &input);
+  viz_ast(&item_fn, &report);
 
-  // viz_token_stream(
-  //   &format!("{} {}", "output of ", output_token_stream_str),
-  //   &output,
-  // );
+  let report_str = report.to_string();
+  let report_items_ts_iter = report_options
+    .iter()
+    .map(|report_option| match report_option {
+      ReportOption::Const(const_ident) => quote! {
+        const #const_ident: &str = #report_str;
+      },
+      ReportOption::Warning => gen_warning_ts(&item_fn.sig.ident, &report_str),
+    });
 
-  viz_ast(ast_item_fn);
+  quote! {
+    #item_fn
+    #(#report_items_ts_iter)*
+  }
+  .into()
+}
 
-  output
+/// There is no stable API for a proc macro to emit a warning. The workaround is to
+/// generate a deprecated item (w/ the report as the note) and then use it. The compiler
+/// then prints the report as a `deprecated` warning that points at the function.
+fn gen_warning_ts(
+  fn_ident: &Ident,
+  report_str: &str,
+) -> proc_macro2::TokenStream {
+  let warning_note_str = format!("\n{}", report_str);
+  let warning_ident = Ident::new(
+    &format!("ast_viz_debug_report_for_{}", fn_ident),
+    fn_ident.span(),
+  );
+  quote! {
+    const _: () = {
+      #[deprecated(note = #warning_note_str)]
+      #[allow(non_camel_case_types)]
+      struct #warning_ident;
+      let _ = #warning_ident;
+    };
+  }
 }
 
 /// https://docs.rs/syn/latest/syn/fn.parse_str.html
@@ -49,51 +96,158 @@ pub fn fn_proc_macro_impl(_input: TokenStream) -> TokenStream {
 /// https://docs.rs/syn/latest/syn/struct.Block.html
 /// https://docs.rs/syn/latest/syn/enum.Stmt.html
 /// https://github.com/dtolnay/proc-macro-workshop#debugging-tips
-fn viz_ast(ast: ItemFn) {
+fn viz_ast(
+  ast: &ItemFn,
+  report: &AstVizReport,
+) {
   // Simply dump the AST to the console.
-  let ast_clone = ast.clone();
+  eprintln!("{} => {:#?}", style_primary("Debug::ast"), ast);
+
+  // Dump the summary of the AST to the console.
   eprintln!(
-    "{} => {:#?}",
-    style_primary("Debug::ast"),
-    ast_clone
+    "{} ast_item_fn {{\n{}}}",
+    style_primary("=>"),
+    style_prompt(&report.to_string())
   );
+}
 
-  // Parse AST to dump some items to the console.
-  let ItemFn {
-    attrs,
-    vis,
-    sig,
-    block,
-  } = ast;
+/// Example of syntax to parse:
+/// ```ignore
+/// fn_macro_ast_viz_debug! {
+///   report = const FOO_AST_REPORT, warning;
+///   fn foo() -> u32 { 42 }
+/// }
+/// ```
+///
+/// The `report = ...;` line is optional. It takes a comma separated list of:
+/// - `const <IDENT>` - generates `const <IDENT>: &str` holding the report.
+/// - `warning` - emits the report as a compile time warning.
+struct AstVizDebugInfo {
+  report_options: Vec<ReportOption>,
+  item_fn: ItemFn,
+}
 
-  eprintln!(
-    "{} ast_item_fn {{ attrs.len:{}, vis:{}, sig:'{}' stmt: '{}' }}",
-    style_primary("=>"),
-    style_prompt(&attrs.len().to_string()),
-    style_prompt(match vis {
-      syn::Visibility::Public(_) => "public",
-      syn::Visibility::Crate(_) => "crate",
-      syn::Visibility::Restricted(_) => "restricted",
-      syn::Visibility::Inherited => "inherited",
-    }),
-    style_prompt(&sig.ident.to_string()),
-    style_prompt(&match block.stmts.first() {
-      Some(stmt) => {
-        let expr_str = stmt
-          .to_token_stream()
-          .to_string()
-          .clone();
-        expr_str
+enum ReportOption {
+  Const(Ident),
+  Warning,
+}
+
+/// [Parse docs](https://docs.rs/syn/latest/syn/parse/index.html)
+impl Parse for AstVizDebugInfo {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let mut report_options = Vec::new();
+
+    // 👀 Optional report options, eg: `report = const FOO_AST_REPORT, warning;`.
+    if input.peek(kw::report) {
+      input.parse::<kw::report>()?;
+      input.parse::<Token![=]>()?;
+      loop {
+        report_options.push(input.parse::<ReportOption>()?);
+        if input.peek(Token![;]) {
+          break;
+        }
+        input.parse::<Token![,]>()?;
       }
-      None => "empty".to_string(),
-    }),
-  );
+      input.parse::<Token![;]>()?;
+    }
+
+    // 👀 Function item, eg: `fn foo() -> u32 { 42 }`.
+    let item_fn: ItemFn = input.parse()?;
+
+    Ok(AstVizDebugInfo {
+      report_options,
+      item_fn,
+    })
+  }
 }
 
-// fn viz_token_stream(
-//   msg: &str,
-//   token_stream: &TokenStream,
-// ) {
-//   eprint_header(msg);
-//   eprintln!("{:#?}", token_stream);
-// }
+impl Parse for ReportOption {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let lookahead = input.lookahead1();
+    if lookahead.peek(Token![const]) {
+      input.parse::<Token![const]>()?;
+      Ok(ReportOption::Const(input.parse()?))
+    } else if lookahead.peek(kw::warning) {
+      input.parse::<kw::warning>()?;
+      Ok(ReportOption::Warning)
+    } else {
+      Err(lookahead.error())
+    }
+  }
+}
+
+/// Summary of the interesting parts of an [ItemFn]. The [Display] impl produces one
+/// `key: value` line per field, eg:
+/// ```text
+///   ident: foo
+///   vis: public
+///   attrs: 1 [inline]
+///   generics: 1 [T : Display]
+///   args: 2 [a : u32, b : T]
+///   return_type: u32
+///   stmts: 1
+/// ```
+struct AstVizReport {
+  ident: String,
+  vis: &'static str,
+  attrs: Vec<String>,
+  generics: Vec<String>,
+  args: Vec<String>,
+  return_type: String,
+  stmts_count: usize,
+}
+
+impl From<&ItemFn> for AstVizReport {
+  fn from(item_fn: &ItemFn) -> Self {
+    let ItemFn {
+      attrs,
+      vis,
+      sig,
+      block,
+    } = item_fn;
+
+    fn to_string_vec<T: ToTokens>(iter: impl Iterator<Item = T>) -> Vec<String> {
+      iter
+        .map(|it| it.to_token_stream().to_string())
+        .collect()
+    }
+
+    AstVizReport {
+      ident: sig.ident.to_string(),
+      vis: match vis {
+        syn::Visibility::Public(_) => "public",
+        syn::Visibility::Crate(_) => "crate",
+        syn::Visibility::Restricted(_) => "restricted",
+        syn::Visibility::Inherited => "inherited",
+      },
+      attrs: to_string_vec(attrs.iter().map(|attr| &attr.path)),
+      generics: to_string_vec(sig.generics.params.iter()),
+      args: to_string_vec(sig.inputs.iter()),
+      return_type: match &sig.output {
+        ReturnType::Default => "()".to_string(),
+        ReturnType::Type(_, ty) => ty.to_token_stream().to_string(),
+      },
+      stmts_count: block.stmts.len(),
+    }
+  }
+}
+
+impl Display for AstVizReport {
+  fn fmt(
+    &self,
+    f: &mut fmt::Formatter<'_>,
+  ) -> fmt::Result {
+    writeln!(f, "  ident: {}", self.ident)?;
+    writeln!(f, "  vis: {}", self.vis)?;
+    writeln!(f, "  attrs: {} [{}]", self.attrs.len(), self.attrs.join(", "))?;
+    writeln!(
+      f,
+      "  generics: {} [{}]",
+      self.generics.len(),
+      self.generics.join(", ")
+    )?;
+    writeln!(f, "  args: {} [{}]", self.args.len(), self.args.join(", "))?;
+    writeln!(f, "  return_type: {}", self.return_type)?;
+    writeln!(f, "  stmts: {}", self.stmts_count)
+  }
+}
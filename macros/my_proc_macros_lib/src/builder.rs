@@ -16,7 +16,7 @@
 */
 
 use quote::quote;
-use syn::{parse_macro_input, DataStruct, DeriveInput};
+use syn::{parse_macro_input, DataStruct, DeriveInput, Error};
 
 use super::utils::{data_ext::DataExt,
                    ident_ext::IdentExt,
@@ -37,6 +37,16 @@ pub fn derive_proc_macro_impl(input: proc_macro::TokenStream) -> proc_macro::Tok
 
   let required_trait_bounds: Vec<&str> = vec!["std::default::Default", "std::fmt::Debug"];
 
+  // Tuple structs, enums & unions don't have named fields to generate setters for.
+  if !data.is_struct_with_named_fields() {
+    return Error::new(
+      struct_name_ident.span(),
+      "Builder can only be derived for a struct w/ named fields",
+    )
+    .to_compile_error()
+    .into();
+  }
+
   // Only generate code for struct.
   if data.is_struct() {
     with_data_struct_make_ts(&data, &|data_struct| {
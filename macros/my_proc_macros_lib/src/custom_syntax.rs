@@ -28,7 +28,9 @@ use syn::{parse::{Parse, ParseBuffer, ParseStream},
           parse2,
           parse_macro_input,
           punctuated::Punctuated,
+          spanned::Spanned,
           token::Comma,
+          Error,
           Expr,
           GenericArgument,
           GenericParam,
@@ -152,11 +154,11 @@ impl Parse for ManagerOfThingInfo {
     // 👀 Thing Type, eg: `std::collections::HashMap<K, V>`.
     let thing_ty: Type = input.parse()?;
 
-    let manager_name_ident = if manager_ty.has_ident() {
-      manager_ty.get_ident().unwrap()
-    } else {
-      panic!("Expected Type::Path::TypePath.segments to have an Ident")
-    };
+    let manager_name_ident = manager_ty
+      .get_last_segment_ident()
+      .ok_or_else(|| {
+        Error::new(manager_ty.span(), "expected the name of a type, eg: `ThingManager<K, V>`")
+      })?;
 
     Ok(ManagerOfThingInfo {
       manager_ty_generic_args,
@@ -16,35 +16,78 @@
 */
 
 use proc_macro::{self, TokenStream};
-use quote::quote;
+use proc_macro2::{Delimiter, Spacing, TokenTree};
+use quote::{quote, ToTokens};
 use syn::{parse_macro_input,
+          Attribute,
+          Data,
           Data::{Enum, Struct, Union},
           DataEnum,
           DataStruct,
-          DataUnion,
           DeriveInput,
+          Error,
+          Field,
           Fields::{Named, Unit, Unnamed},
           FieldsNamed,
-          FieldsUnnamed};
+          FieldsUnnamed,
+          Result,
+          Type};
 
+use crate::utils::attrs::AttrArgs;
+
+const DESCRIBE_ATTR: &str = "describe";
+
+/// Generates an impl of the `Describe` trait (resolved at the call site) which returns a
+/// human readable description of the type.
+///
+/// W/ `#[describe(fields)]` on the type, it also generates the following machine readable
+/// metadata as inherent items on the type:
+/// 1. `const FIELDS: &'static [FieldMeta]` - the name, type name & index of each field.
+/// 2. `fn field_names() -> &'static [&'static str]` - just the names.
+///
+/// `FieldMeta` is resolved at the call site & is expected to look like this:
+/// ```no_run
+/// struct FieldMeta {
+///   name: &'static str,
+///   type_name: &'static str,
+///   index: usize,
+/// }
+/// ```
+///
+/// Unnamed fields are named by their index (eg: `"0"`). Enums and unit structs don't have
+/// any fields. Unions aren't supported.
 pub fn derive_proc_macro_impl(input: TokenStream) -> TokenStream {
   let DeriveInput {
     ident: struct_name_ident,
+    attrs,
     data,
     generics,
     ..
   } = parse_macro_input!(input as DeriveInput); // Same as: syn::parse(input).unwrap();
 
-  let where_clause = &generics.where_clause;
-
-  let description_str = match data {
+  let description_str = match &data {
     Struct(my_struct) => gen_description_str_for_struct(my_struct),
     Enum(my_enum) => gen_description_str_for_enum(my_enum),
-    Union(my_union) => gen_description_str_for_union(my_union),
+    Union(_) => {
+      return Error::new(
+        struct_name_ident.span(),
+        "Describe can only be derived for a struct or an enum, not a union",
+      )
+      .to_compile_error()
+      .into()
+    }
   };
 
+  let field_metadata_ts = match parse_describe_fields_flag(&attrs) {
+    Ok(true) => gen_field_metadata_ts(&struct_name_ident, &generics, &data),
+    Ok(false) => quote! {},
+    Err(error) => return error.to_compile_error().into(),
+  };
+
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
   quote! {
-    impl #generics Describe for #struct_name_ident #generics #where_clause {
+    impl #impl_generics Describe for #struct_name_ident #ty_generics #where_clause {
       fn describe() -> String {
         let mut string = String::from(stringify!(#struct_name_ident));
         string.push_str(" is ");
@@ -52,40 +95,159 @@ pub fn derive_proc_macro_impl(input: TokenStream) -> TokenStream {
         string
       }
     }
+
+    #field_metadata_ts
   }
   .into()
 }
 
-fn gen_description_str_for_union(my_union: DataUnion) -> String {
-  handle_named_fields(my_union.fields)
+/// Parses the `#[describe(...)]` attributes on the type, eg: `#[describe(fields)]`.
+fn parse_describe_fields_flag(attrs: &[Attribute]) -> Result<bool> {
+  let mut attr_args = AttrArgs::parse(attrs, DESCRIBE_ATTR)?;
+  let has_fields_flag = attr_args.take_flag("fields")?;
+  attr_args.finish("unknown arg, expected `fields`")?;
+  Ok(has_fields_flag)
+}
+
+fn gen_field_metadata_ts(
+  struct_name_ident: &syn::Ident,
+  generics: &syn::Generics,
+  data: &Data,
+) -> proc_macro2::TokenStream {
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  let fields: Vec<&Field> = match data {
+    Struct(my_struct) => my_struct.fields.iter().collect(),
+    Enum(_) | Union(_) => vec![],
+  };
+
+  let field_name_str_vec: Vec<String> = fields
+    .iter()
+    .enumerate()
+    .map(|(index, field)| match &field.ident {
+      Some(field_ident) => field_ident.to_string(),
+      None => index.to_string(),
+    })
+    .collect();
+
+  let field_meta_ts_iter = fields
+    .iter()
+    .zip(field_name_str_vec.iter())
+    .enumerate()
+    .map(|(index, (field, field_name_str))| {
+      let type_name_str = gen_type_name_str(&field.ty);
+      quote! {
+        FieldMeta {
+          name: #field_name_str,
+          type_name: #type_name_str,
+          index: #index,
+        }
+      }
+    });
+
+  quote! {
+    impl #impl_generics #struct_name_ident #ty_generics #where_clause {
+      pub const FIELDS: &'static [FieldMeta] = &[#(#field_meta_ts_iter),*];
+
+      pub fn field_names() -> &'static [&'static str] {
+        &[#(#field_name_str_vec),*]
+      }
+    }
+  }
+}
+
+/// Writes out the tokens of a type the way it is usually written, eg: `Vec<String>` or
+/// `&'a str`, rather than w/ a space between every token (like `to_string()` does).
+fn gen_type_name_str(ty: &Type) -> String {
+  let mut type_name_str = String::new();
+  write_tokens(ty.to_token_stream(), &mut type_name_str);
+  type_name_str
+}
+
+/// Words (idents & literals) are separated by a space, as are the punctuation marks that
+/// separate parts of a type (eg: `,` in `HashMap<K, V>`, `+` in `dyn Read + Send`, `->` in
+/// `fn(u8) -> u8`). Everything else is joined, eg: `::`, `<`, `&`, `'a`, or the args of
+/// `Fn(u8)` (but not the tuple in `&mut (u8, u8)`).
+fn write_tokens(
+  ts: proc_macro2::TokenStream,
+  output: &mut String,
+) {
+  let mut prev_word: Option<String> = None;
+  let mut prev_is_joint_minus = false;
+  for tt in ts {
+    let is_joint_minus = matches!(
+      &tt,
+      TokenTree::Punct(punct) if punct.as_char() == '-' && punct.spacing() == Spacing::Joint
+    );
+    match &tt {
+      TokenTree::Ident(_) | TokenTree::Literal(_) => {
+        if prev_word.is_some() {
+          output.push(' ');
+        }
+        output.push_str(&tt.to_string());
+      }
+      TokenTree::Punct(punct) => match punct.as_char() {
+        ',' | ';' => output.push_str(&format!("{} ", punct.as_char())),
+        '+' | '=' => output.push_str(&format!(" {} ", punct.as_char())),
+        '-' if is_joint_minus => output.push_str(" -"),
+        '>' if prev_is_joint_minus => output.push_str("> "),
+        ':' if punct.spacing() == Spacing::Alone && !output.ends_with(':') => {
+          output.push_str(": ")
+        }
+        char => output.push(char),
+      },
+      TokenTree::Group(group) => {
+        let (open, close) = match group.delimiter() {
+          Delimiter::Parenthesis => ("(", ")"),
+          Delimiter::Bracket => ("[", "]"),
+          Delimiter::Brace => ("{ ", " }"),
+          Delimiter::None => ("", ""),
+        };
+        let is_call_args = group.delimiter() == Delimiter::Parenthesis
+          && !matches!(prev_word.as_deref(), Some("mut" | "const" | "dyn" | "impl"));
+        if prev_word.is_some() && !is_call_args {
+          output.push(' ');
+        }
+        output.push_str(open);
+        write_tokens(group.stream(), output);
+        output.push_str(close);
+      }
+    }
+    prev_word = match &tt {
+      TokenTree::Ident(_) | TokenTree::Literal(_) => Some(tt.to_string()),
+      _ => None,
+    };
+    prev_is_joint_minus = is_joint_minus;
+  }
 }
 
-fn gen_description_str_for_struct(my_struct: DataStruct) -> String {
-  match my_struct.fields {
+fn gen_description_str_for_struct(my_struct: &DataStruct) -> String {
+  match &my_struct.fields {
     Named(fields) => handle_named_fields(fields),
     Unnamed(fields) => handle_unnamed_fields(fields),
     Unit => handle_unit(),
   }
 }
 
-fn handle_named_fields(fields: FieldsNamed) -> String {
+fn handle_named_fields(fields: &FieldsNamed) -> String {
   let my_named_field_idents = fields
     .named
-    .iter();
+    .iter()
+    .map(|it| &it.ident);
   format!(
     "a struct with these named fields: {}",
     quote! {#(#my_named_field_idents), *}
   )
 }
 
-fn handle_unnamed_fields(fields: FieldsUnnamed) -> String {
+fn handle_unnamed_fields(fields: &FieldsUnnamed) -> String {
   let my_unnamed_fields_count = fields.unnamed.iter().count();
   format!("a struct with {} unnamed fields", my_unnamed_fields_count)
 }
 
 fn handle_unit() -> String { format!("a unit struct") }
 
-fn gen_description_str_for_enum(my_enum: DataEnum) -> String {
+fn gen_description_str_for_enum(my_enum: &DataEnum) -> String {
   let my_variant_idents = my_enum
     .variants
     .iter()
@@ -95,3 +257,22 @@ fn gen_description_str_for_enum(my_enum: DataEnum) -> String {
     quote! {#(#my_variant_idents),*}
   )
 }
+
+#[test]
+fn test_gen_type_name_str() {
+  let assert_type_name_str = |type_str: &str| {
+    let ty = syn::parse_str::<Type>(type_str).unwrap();
+    assert_eq!(gen_type_name_str(&ty), type_str);
+  };
+  assert_type_name_str("String");
+  assert_type_name_str("Vec<Vec<T>>");
+  assert_type_name_str("&'a str");
+  assert_type_name_str("&mut [u8]");
+  assert_type_name_str("&mut (u8, u8)");
+  assert_type_name_str("[u8; 4]");
+  assert_type_name_str("std::collections::HashMap<K, V>");
+  assert_type_name_str("(u8, Option<String>)");
+  assert_type_name_str("Box<dyn Fn(u8) -> u8 + Send + 'static>");
+  assert_type_name_str("<T as Iterator>::Item");
+  assert_type_name_str("*const T");
+}
@@ -0,0 +1,133 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+use quote::quote;
+use syn::{parse_macro_input,
+          spanned::Spanned,
+          Data,
+          DeriveInput,
+          Error,
+          Field,
+          Fields,
+          Result};
+
+use crate::utils::attrs::get_flag_attrs;
+
+const HAS_ID_ATTR: &str = "has_id";
+
+/// Generates an impl of the `HasId` trait (resolved at the call site) for a struct w/
+/// named fields, eg:
+/// ```ignore
+/// #[derive(HasId)]
+/// struct Payload {
+///   #[has_id]
+///   key: usize,
+///   name: String,
+/// }
+/// ```
+///
+/// The id is the field marked w/ `#[has_id]`, or the field named `id` if no field is
+/// marked. The trait is expected to look like this:
+/// ```no_run
+/// trait HasId {
+///   type Id;
+///   fn id(&self) -> &Self::Id;
+/// }
+/// ```
+pub fn derive_proc_macro_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+  let derive_input = parse_macro_input!(input as DeriveInput);
+  match gen_has_id_impl_ts(&derive_input) {
+    Ok(ts) => ts,
+    Err(error) => error.to_compile_error(),
+  }
+  .into()
+}
+
+fn gen_has_id_impl_ts(derive_input: &DeriveInput) -> Result<proc_macro2::TokenStream> {
+  let DeriveInput {
+    ident: struct_name_ident,
+    generics,
+    data,
+    ..
+  } = derive_input;
+
+  let id_field = find_id_field(struct_name_ident, data)?;
+  let id_field_ident = &id_field.ident;
+  let id_field_ty = &id_field.ty;
+
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    impl #impl_generics HasId for #struct_name_ident #ty_generics #where_clause {
+      type Id = #id_field_ty;
+
+      fn id(&self) -> &Self::Id {
+        &self.#id_field_ident
+      }
+    }
+  })
+}
+
+fn find_id_field<'a>(
+  struct_name_ident: &syn::Ident,
+  data: &'a Data,
+) -> Result<&'a Field> {
+  let named_fields = match data {
+    Data::Struct(data_struct) => match &data_struct.fields {
+      Fields::Named(fields_named) => &fields_named.named,
+      _ => {
+        return Err(Error::new(
+          struct_name_ident.span(),
+          "HasId can only be derived for a struct w/ named fields",
+        ))
+      }
+    },
+    _ => {
+      return Err(Error::new(
+        struct_name_ident.span(),
+        "HasId can only be derived for a struct",
+      ))
+    }
+  };
+
+  // 👀 Field marked w/ `#[has_id]`.
+  let mut marked_field: Option<&Field> = None;
+  for field in named_fields.iter() {
+    for attr in get_flag_attrs(&field.attrs, HAS_ID_ATTR)? {
+      if marked_field.replace(field).is_some() {
+        return Err(Error::new(
+          attr.span(),
+          "only one field can be marked w/ `#[has_id]`",
+        ));
+      }
+    }
+  }
+
+  // 👀 Otherwise, field named `id`.
+  marked_field
+    .or_else(|| {
+      named_fields
+        .iter()
+        .find(|field| matches!(&field.ident, Some(ident) if ident == "id"))
+    })
+    .ok_or_else(|| {
+      Error::new(
+        struct_name_ident.span(),
+        "HasId needs a field named `id`, or a field marked w/ `#[has_id]`",
+      )
+    })
+}
@@ -0,0 +1,306 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+use std::collections::HashMap;
+
+use quote::quote;
+use syn::{parse::{Parse, ParseStream},
+          parse_macro_input,
+          punctuated::Punctuated,
+          token::Comma,
+          Error,
+          Expr,
+          LitStr,
+          Result,
+          Token};
+
+const NAMED_KEYS: &[&str] = &[
+  "enter",
+  "esc",
+  "tab",
+  "backtab",
+  "backspace",
+  "delete",
+  "insert",
+  "home",
+  "end",
+  "pageup",
+  "pagedown",
+  "up",
+  "down",
+  "left",
+  "right",
+  "space",
+];
+
+/// See [`KeymapInfo`] for more information on the syntax that this macro accepts.
+///
+/// Expands into a `Vec` of bindings, in the order that they are declared. The names
+/// `KeyBinding` & `KeyChord` are resolved at the call site, & are expected to look like
+/// this:
+/// ```no_run
+/// struct KeyBinding<A> {
+///   chords: Vec<KeyChord>,
+///   action: A,
+/// }
+///
+/// struct KeyChord {
+///   key: &'static str,
+///   ctrl: bool,
+///   alt: bool,
+///   shift: bool,
+/// }
+/// ```
+///
+/// Letter keys are lowercase (use `shift` for uppercase), and the other keys are one of
+/// [NAMED_KEYS], `f1` to `f12`, or a single (non whitespace) character.
+pub fn fn_proc_macro_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+  let KeymapInfo { entry_vec } = parse_macro_input!(input as KeymapInfo);
+
+  let bindings_ts_iter = entry_vec.iter().map(|entry| {
+    let chords_ts_iter = entry.chord_vec.iter().map(|chord| {
+      let KeyChord {
+        key,
+        ctrl,
+        alt,
+        shift,
+      } = chord;
+      quote! {
+        KeyChord {
+          key: #key,
+          ctrl: #ctrl,
+          alt: #alt,
+          shift: #shift,
+        }
+      }
+    });
+    let action_expr = &entry.action_expr;
+    quote! {
+      KeyBinding {
+        chords: vec![#(#chords_ts_iter),*],
+        action: #action_expr,
+      }
+    }
+  });
+
+  quote! {
+    vec![#(#bindings_ts_iter),*]
+  }
+  .into()
+}
+
+/// Example of syntax to parse:
+/// ```ignore
+/// keymap! {
+///   "ctrl+shift+p" => Action::OpenPalette,
+///   "g g" => Action::GoTop,
+/// }
+/// ```
+///
+/// - Each key sequence is a string of space separated chords, which must all be typed (in
+///   order) to trigger the action.
+/// - Each chord is `+` separated modifiers (`ctrl`, `alt`, `shift`) followed by a key.
+/// - The action can be any expression.
+///
+/// It is a compile error if a key sequence is bound more than once (no matter the order of
+/// the modifiers), or if it is the start of another one (eg: `"g"` & `"g g"`), since the
+/// longer one could never be typed.
+struct KeymapInfo {
+  entry_vec: Vec<KeymapEntry>,
+}
+
+struct KeymapEntry {
+  chord_vec: Vec<KeyChord>,
+  action_expr: Expr,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct KeyChord {
+  key: String,
+  ctrl: bool,
+  alt: bool,
+  shift: bool,
+}
+
+/// [Parse docs](https://docs.rs/syn/latest/syn/parse/index.html)
+impl Parse for KeymapInfo {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let entry_vec: Vec<(LitStr, KeymapEntry)> =
+      Punctuated::<(LitStr, KeymapEntry), Comma>::parse_terminated_with(input, parse_entry)?
+        .into_iter()
+        .collect();
+
+    // 👀 Duplicate key sequences, or ones that start w/ another one.
+    let mut lit_map: HashMap<&[KeyChord], &LitStr> = HashMap::new();
+    for (lit, entry) in entry_vec.iter() {
+      if let Some(first_lit) = lit_map.get(entry.chord_vec.as_slice()) {
+        return Err(Error::new(
+          lit.span(),
+          format!(
+            "`{}` is bound more than once, it is the same as `{}`",
+            lit.value(),
+            first_lit.value()
+          ),
+        ));
+      }
+      lit_map.insert(entry.chord_vec.as_slice(), lit);
+    }
+    for (lit, entry) in entry_vec.iter() {
+      for prefix_len in 1..entry.chord_vec.len() {
+        if let Some(prefix_lit) = lit_map.get(&entry.chord_vec[..prefix_len]) {
+          return Err(Error::new(
+            lit.span(),
+            format!(
+              "`{}` can never be typed, since `{}` is bound to another action",
+              lit.value(),
+              prefix_lit.value()
+            ),
+          ));
+        }
+      }
+    }
+
+    Ok(KeymapInfo {
+      entry_vec: entry_vec
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .collect(),
+    })
+  }
+}
+
+/// Parses eg: `"ctrl+shift+p" => Action::OpenPalette`. The literal is returned too, so that
+/// errors about the whole keymap can point at it.
+fn parse_entry(input: ParseStream) -> Result<(LitStr, KeymapEntry)> {
+  // 👀 Key sequence, eg: `"ctrl+shift+p"`.
+  let lit: LitStr = input.parse()?;
+  let chord_vec = parse_key_sequence(&lit)?;
+
+  // 👀 => token.
+  input.parse::<Token![=>]>()?;
+
+  // 👀 Action, eg: `Action::OpenPalette`.
+  let action_expr: Expr = input.parse()?;
+
+  Ok((
+    lit,
+    KeymapEntry {
+      chord_vec,
+      action_expr,
+    },
+  ))
+}
+
+fn parse_key_sequence(lit: &LitStr) -> Result<Vec<KeyChord>> {
+  let chord_vec = lit
+    .value()
+    .split_whitespace()
+    .map(|chord_str| parse_chord(lit, chord_str))
+    .collect::<Result<Vec<KeyChord>>>()?;
+  if chord_vec.is_empty() {
+    return Err(Error::new(lit.span(), "expected at least one key, eg: `\"ctrl+p\"`"));
+  }
+  Ok(chord_vec)
+}
+
+fn parse_chord(
+  lit: &LitStr,
+  chord_str: &str,
+) -> Result<KeyChord> {
+  // `+` on its own (or at the end, eg: `ctrl++`) is the `+` key, not a separator.
+  let (modifiers_str, key) = match chord_str.strip_suffix('+') {
+    Some(rest) if rest.is_empty() || rest.ends_with('+') => {
+      (rest.strip_suffix('+').unwrap_or(rest), "+".to_string())
+    }
+    _ => match chord_str.rsplit_once('+') {
+      Some((modifiers_str, key)) => (modifiers_str, key.to_string()),
+      None => ("", chord_str.to_string()),
+    },
+  };
+
+  let mut chord = KeyChord {
+    key: parse_key(lit, chord_str, key)?,
+    ctrl: false,
+    alt: false,
+    shift: false,
+  };
+
+  for modifier in modifiers_str
+    .split('+')
+    .filter(|it| !it.is_empty())
+  {
+    let is_duplicate = match modifier {
+      "ctrl" => std::mem::replace(&mut chord.ctrl, true),
+      "alt" => std::mem::replace(&mut chord.alt, true),
+      "shift" => std::mem::replace(&mut chord.shift, true),
+      _ => {
+        return Err(Error::new(
+          lit.span(),
+          format!(
+            "unknown modifier `{}` in `{}`, expected `ctrl`, `alt` or `shift`",
+            modifier, chord_str
+          ),
+        ))
+      }
+    };
+    if is_duplicate {
+      return Err(Error::new(
+        lit.span(),
+        format!("`{}` is repeated in `{}`", modifier, chord_str),
+      ));
+    }
+  }
+
+  Ok(chord)
+}
+
+fn parse_key(
+  lit: &LitStr,
+  chord_str: &str,
+  key: String,
+) -> Result<String> {
+  let mut chars = key.chars();
+  let is_single_char = matches!((chars.next(), chars.next()), (Some(_), None));
+  let is_function_key = matches!(
+    key.strip_prefix('f').map(str::parse::<u8>),
+    Some(Ok(1..=12))
+  );
+
+  if is_single_char && key.chars().all(|it| it.is_ascii_uppercase()) {
+    Err(Error::new(
+      lit.span(),
+      format!(
+        "use `shift+{}` instead of `{}` in `{}`",
+        key.to_lowercase(),
+        key,
+        chord_str
+      ),
+    ))
+  } else if is_single_char || is_function_key || NAMED_KEYS.contains(&key.as_str()) {
+    Ok(key)
+  } else {
+    Err(Error::new(
+      lit.span(),
+      format!(
+        "unknown key `{}` in `{}`, expected a single character, `f1` to `f12`, or one of: {}",
+        key,
+        chord_str,
+        NAMED_KEYS.join(", ")
+      ),
+    ))
+  }
+}
@@ -0,0 +1,260 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+use proc_macro2::Span;
+use quote::quote;
+use syn::{braced,
+          bracketed,
+          parenthesized,
+          parse::{Parse, ParseStream},
+          parse_macro_input,
+          punctuated::Punctuated,
+          token::Comma,
+          Error,
+          Expr,
+          Ident,
+          Result,
+          Token};
+
+/// See [`LayoutTreeInfo`] for more information on the syntax that this macro accepts.
+///
+/// Each layout in the tree expands into a `start_layout()` call, followed by an optional
+/// `print()` call for its `text`, then the expansion of its `children`, and finally the
+/// matching `end_layout()` call. Since the calls are generated from the nesting of the
+/// tree, it is not possible to write a `start_layout()` w/out its `end_layout()`.
+///
+/// The layout manager expression is evaluated once, & all the calls are made on a mutable
+/// borrow of it.
+pub fn fn_proc_macro_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+  let LayoutTreeInfo {
+    manager_expr,
+    layout_node_vec,
+  } = parse_macro_input!(input as LayoutTreeInfo);
+
+  let manager_ident = Ident::new("__layout_manager", Span::mixed_site());
+
+  let layouts_ts_iter = layout_node_vec
+    .iter()
+    .map(|layout_node| gen_layout_ts(&manager_ident, layout_node));
+
+  quote! {
+    {
+      let #manager_ident = &mut (#manager_expr);
+      #(#layouts_ts_iter)*
+    }
+  }
+  .into()
+}
+
+/// Returns [proc_macro2::TokenStream] (not [proc_macro::TokenStream]).
+///
+/// The names `LayoutProps`, `Direction`, `RequestedSizePercent` & `Pair` are resolved at
+/// the call site, and the enclosing fn must return a `Result` so that `?` can be used.
+fn gen_layout_ts(
+  manager_ident: &Ident,
+  layout_node: &LayoutNode,
+) -> proc_macro2::TokenStream {
+  let LayoutNode {
+    id,
+    dir,
+    size: (width, height),
+    styles,
+    text,
+    children,
+  } = layout_node;
+
+  let styles_ts = match styles {
+    Some(styles) => quote! { Some(vec![#(#styles),*]) },
+    None => quote! { None },
+  };
+
+  let print_ts = match text {
+    Some(text) => quote! { #manager_ident.print(vec![#(#text),*])?; },
+    None => quote! {},
+  };
+
+  let children_ts_iter = children
+    .iter()
+    .map(|child_node| gen_layout_ts(manager_ident, child_node));
+
+  quote! {
+    #manager_ident.start_layout(LayoutProps {
+      id: #id.to_string(),
+      dir: Direction::#dir,
+      req_size: RequestedSizePercent::parse_pair(Pair::new(#width, #height))?,
+      styles: #styles_ts,
+    })?;
+    #print_ts
+    #(#children_ts_iter)*
+    #manager_ident.end_layout()?;
+  }
+}
+
+/// Example of syntax to parse:
+/// ```ignore
+/// layout! {
+///   canvas =>
+///   {
+///     id: "container",
+///     dir: Horizontal,
+///     size: (100, 100),
+///     children: [
+///       {
+///         id: "col_1",
+///         dir: Vertical,
+///         size: (50, 100),
+///         styles: [style_1],
+///         text: ["col 1 - Hello", "col 1 - World"],
+///       },
+///       { id: "col_2", dir: Vertical, size: (50, 100) },
+///     ],
+///   }
+/// }
+/// ```
+///
+/// - `canvas` is any expression that has `start_layout()`, `end_layout()` & `print()`
+///   methods, eg: a `Canvas` that implements `LayoutManager`. It is mutably borrowed, so
+///   use `*canvas` when `canvas` is a `&mut Canvas` (that isn't a `mut` binding).
+/// - One or more (comma separated) layouts can follow the `=>`.
+/// - `id`, `dir` & `size` are required, `styles`, `text` & `children` are optional.
+struct LayoutTreeInfo {
+  manager_expr: Expr,
+  layout_node_vec: Vec<LayoutNode>,
+}
+
+struct LayoutNode {
+  id: Expr,
+  dir: Ident,
+  size: (Expr, Expr),
+  styles: Option<Vec<Expr>>,
+  text: Option<Vec<Expr>>,
+  children: Vec<LayoutNode>,
+}
+
+/// [Parse docs](https://docs.rs/syn/latest/syn/parse/index.html)
+impl Parse for LayoutTreeInfo {
+  fn parse(input: ParseStream) -> Result<Self> {
+    // 👀 Layout manager, eg: `canvas`.
+    let manager_expr: Expr = input.parse()?;
+
+    // 👀 => token.
+    input.parse::<Token![=>]>()?;
+
+    // 👀 Layouts, eg: `{ id: "container", ... }, { id: "footer", ... }`.
+    let layout_node_vec: Vec<LayoutNode> =
+      Punctuated::<LayoutNode, Comma>::parse_terminated(input)?
+        .into_iter()
+        .collect();
+
+    if layout_node_vec.is_empty() {
+      return Err(input.error("expected at least one layout after `=>`"));
+    }
+
+    Ok(LayoutTreeInfo {
+      manager_expr,
+      layout_node_vec,
+    })
+  }
+}
+
+impl Parse for LayoutNode {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let content;
+    let brace_token = braced!(content in input);
+
+    let mut id: Option<Expr> = None;
+    let mut dir: Option<Ident> = None;
+    let mut size: Option<(Expr, Expr)> = None;
+    let mut styles: Option<Vec<Expr>> = None;
+    let mut text: Option<Vec<Expr>> = None;
+    let mut children: Option<Vec<LayoutNode>> = None;
+
+    while !content.is_empty() {
+      // 👀 Key, eg: `id:`.
+      let key: Ident = content.parse()?;
+      content.parse::<Token![:]>()?;
+
+      let is_duplicate = match key.to_string().as_str() {
+        "id" => id.replace(content.parse()?).is_some(),
+        "dir" => dir.replace(content.parse()?).is_some(),
+        "size" => size.replace(parse_size(&content)?).is_some(),
+        "styles" => styles.replace(parse_expr_list(&content)?).is_some(),
+        "text" => text.replace(parse_expr_list(&content)?).is_some(),
+        "children" => {
+          let children_content;
+          bracketed!(children_content in content);
+          let children_vec =
+            Punctuated::<LayoutNode, Comma>::parse_terminated(&children_content)?
+              .into_iter()
+              .collect();
+          children.replace(children_vec).is_some()
+        }
+        _ => {
+          return Err(Error::new(
+            key.span(),
+            format!(
+              "unknown key `{}`, expected one of: id, dir, size, styles, text, children",
+              key
+            ),
+          ))
+        }
+      };
+
+      if is_duplicate {
+        return Err(Error::new(key.span(), format!("duplicate key `{}`", key)));
+      }
+
+      // 👀 Optional trailing comma.
+      if !content.is_empty() {
+        content.parse::<Token![,]>()?;
+      }
+    }
+
+    let missing_key_error =
+      |key: &str| Error::new(brace_token.span, format!("layout is missing `{}`", key));
+
+    Ok(LayoutNode {
+      id: id.ok_or_else(|| missing_key_error("id"))?,
+      dir: dir.ok_or_else(|| missing_key_error("dir"))?,
+      size: size.ok_or_else(|| missing_key_error("size"))?,
+      styles,
+      text,
+      children: children.unwrap_or_default(),
+    })
+  }
+}
+
+/// Parses a pair of percentages, eg: `(50, 100)`.
+fn parse_size(input: ParseStream) -> Result<(Expr, Expr)> {
+  let content;
+  parenthesized!(content in input);
+  let width: Expr = content.parse()?;
+  content.parse::<Token![,]>()?;
+  let height: Expr = content.parse()?;
+  Ok((width, height))
+}
+
+/// Parses a list of expressions, eg: `["Hello", "World"]`.
+fn parse_expr_list(input: ParseStream) -> Result<Vec<Expr>> {
+  let content;
+  bracketed!(content in input);
+  Ok(
+    Punctuated::<Expr, Comma>::parse_terminated(&content)?
+      .into_iter()
+      .collect(),
+  )
+}
@@ -27,6 +27,12 @@ mod builder;
 mod utils;
 mod logger;
 mod custom_syntax;
+mod layout;
+mod has_id;
+mod assert_layout;
+mod timed;
+mod keymap;
+mod settings;
 
 #[proc_macro]
 pub fn fn_macro_ast_viz_debug(input: TokenStream) -> TokenStream {
@@ -38,11 +44,36 @@ pub fn fn_macro_custom_syntax(input: TokenStream) -> TokenStream {
   custom_syntax::fn_proc_macro_impl(input)
 }
 
-#[proc_macro_derive(Describe)]
+#[proc_macro]
+pub fn layout(input: TokenStream) -> TokenStream {
+  layout::fn_proc_macro_impl(input)
+}
+
+#[proc_macro]
+pub fn assert_layout(input: TokenStream) -> TokenStream {
+  assert_layout::fn_proc_macro_impl(input)
+}
+
+#[proc_macro]
+pub fn keymap(input: TokenStream) -> TokenStream {
+  keymap::fn_proc_macro_impl(input)
+}
+
+#[proc_macro_derive(Describe, attributes(describe))]
 pub fn derive_macro_describe(input: TokenStream) -> TokenStream {
   describe::derive_proc_macro_impl(input)
 }
 
+#[proc_macro_derive(HasId, attributes(has_id))]
+pub fn derive_macro_has_id(input: TokenStream) -> TokenStream {
+  has_id::derive_proc_macro_impl(input)
+}
+
+#[proc_macro_derive(Settings, attributes(settings))]
+pub fn derive_macro_settings(input: TokenStream) -> TokenStream {
+  settings::derive_proc_macro_impl(input)
+}
+
 #[proc_macro_derive(Builder)]
 pub fn derive_macro_builder(input: TokenStream) -> TokenStream {
   builder::derive_proc_macro_impl(input)
@@ -63,3 +94,11 @@ pub fn attrib_macro_logger_2(
 ) -> TokenStream {
   logger::attrib_proc_macro_impl_2(args, input)
 }
+
+#[proc_macro_attribute]
+pub fn timed(
+  args: TokenStream,
+  input: TokenStream,
+) -> TokenStream {
+  timed::attrib_proc_macro_impl(args, input)
+}
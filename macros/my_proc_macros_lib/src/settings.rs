@@ -0,0 +1,222 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Field, Fields, LitStr, Result};
+
+use crate::utils::attrs::AttrArgs;
+
+const SETTINGS_ATTR: &str = "settings";
+
+/// Generates a `load()` fn for a struct w/ named fields, which fills in each field from
+/// (in priority order):
+/// 1. The `overrides` that are passed to it (eg: from CLI args), by field name.
+/// 2. The env var named in `#[settings(env = "FOO")]`, if there is one.
+/// 3. The TOML file at `toml_path` (if any), by field name.
+/// 4. The default in `#[settings(default)]` (`Default::default()`) or
+///    `#[settings(default = "8080")]` (parsed like the other values).
+///
+/// eg:
+/// ```ignore
+/// #[derive(Settings)]
+/// struct LoggerSettings {
+///   #[settings(env = "LOG_LEVEL", default = "info")]
+///   level: String,
+///   #[settings(default)]
+///   to_file: bool,
+/// }
+///
+/// let settings = LoggerSettings::load(Some(Path::new("logger.toml")), &[("to_file", "true")])?;
+/// ```
+///
+/// Each value is parsed w/ [std::str::FromStr], so the `Err` of each field's type has to
+/// implement `Display`. TOML values that aren't strings are parsed from their TOML text (eg:
+/// `8080`, `true`). Errors name the field & where its value came from. The generated code
+/// uses the `toml` crate, which has to be a dependency of the crate that derives this.
+pub fn derive_proc_macro_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+  let derive_input = parse_macro_input!(input as DeriveInput);
+  match gen_settings_impl_ts(&derive_input) {
+    Ok(ts) => ts,
+    Err(error) => error.to_compile_error(),
+  }
+  .into()
+}
+
+struct FieldSettings<'a> {
+  field: &'a Field,
+  env: Option<LitStr>,
+  default: FieldDefault,
+}
+
+enum FieldDefault {
+  /// The field is required.
+  None,
+  /// `Default::default()`.
+  Trait,
+  /// Parsed like the other values.
+  Str(LitStr),
+}
+
+fn gen_settings_impl_ts(derive_input: &DeriveInput) -> Result<proc_macro2::TokenStream> {
+  let DeriveInput {
+    ident: struct_name_ident,
+    generics,
+    data,
+    ..
+  } = derive_input;
+
+  let named_fields = match data {
+    Data::Struct(data_struct) => match &data_struct.fields {
+      Fields::Named(fields_named) => &fields_named.named,
+      _ => {
+        return Err(Error::new(
+          struct_name_ident.span(),
+          "Settings can only be derived for a struct w/ named fields",
+        ))
+      }
+    },
+    _ => {
+      return Err(Error::new(
+        struct_name_ident.span(),
+        "Settings can only be derived for a struct",
+      ))
+    }
+  };
+
+  let field_ts_vec = named_fields
+    .iter()
+    .map(|field| parse_field_settings(field).map(|it| gen_field_ts(&it)))
+    .collect::<Result<Vec<_>>>()?;
+
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  Ok(quote! {
+    impl #impl_generics #struct_name_ident #ty_generics #where_clause {
+      /// Loads each field from the `overrides`, its env var, the TOML file at
+      /// `toml_path`, or its default (in that order).
+      pub fn load(
+        toml_path: Option<&std::path::Path>,
+        overrides: &[(&str, &str)],
+      ) -> std::result::Result<Self, String> {
+        let toml_table: toml::value::Table = match toml_path {
+          Some(toml_path) => {
+            let content = std::fs::read_to_string(toml_path)
+              .map_err(|error| format!("{}: {}", toml_path.display(), error))?;
+            toml::from_str(&content)
+              .map_err(|error| format!("{}: {}", toml_path.display(), error))?
+          }
+          None => toml::value::Table::new(),
+        };
+
+        // Returns where the value came from (for errors) & the value.
+        let lookup = |key: &str, env: Option<&str>| -> Option<(String, String)> {
+          if let Some((_, value)) = overrides.iter().find(|(it, _)| *it == key) {
+            return Some(("the overrides".to_string(), value.to_string()));
+          }
+          if let Some(env) = env {
+            if let Ok(value) = std::env::var(env) {
+              return Some((format!("the `{}` env var", env), value));
+            }
+          }
+          toml_table.get(key).map(|value| {
+            let value = match value {
+              toml::Value::String(it) => it.clone(),
+              it => it.to_string(),
+            };
+            let toml_path = toml_path.map(|it| it.display().to_string()).unwrap_or_default();
+            (toml_path, value)
+          })
+        };
+
+        Ok(Self {
+          #(#field_ts_vec),*
+        })
+      }
+    }
+  })
+}
+
+fn gen_field_ts(field_settings: &FieldSettings) -> proc_macro2::TokenStream {
+  let FieldSettings {
+    field,
+    env,
+    default,
+  } = field_settings;
+  let field_ident = &field.ident;
+  let field_ty = &field.ty;
+  let key_str = field_ident
+    .as_ref()
+    .map(|it| it.to_string())
+    .unwrap_or_default();
+
+  let env_ts = match env {
+    Some(env) => quote! { Some(#env) },
+    None => quote! { None },
+  };
+
+  let missing_str = match env {
+    Some(env) => format!(
+      "Setting `{}` is missing, set the `{}` env var or `{}` in the TOML file",
+      key_str,
+      env.value(),
+      key_str
+    ),
+    None => format!(
+      "Setting `{}` is missing, set `{}` in the TOML file",
+      key_str, key_str
+    ),
+  };
+
+  let default_ts = match default {
+    FieldDefault::None => quote! { return Err(#missing_str.to_string()) },
+    FieldDefault::Trait => quote! { std::default::Default::default() },
+    FieldDefault::Str(default) => quote! {
+      #default.parse::<#field_ty>().map_err(|error| {
+        format!("Setting `{}` has an invalid default {:?}: {}", #key_str, #default, error)
+      })?
+    },
+  };
+
+  quote! {
+    #field_ident: match lookup(#key_str, #env_ts) {
+      Some((source, value)) => value.parse::<#field_ty>().map_err(|error| {
+        format!("Setting `{}` is invalid, {:?} (from {}): {}", #key_str, value, source, error)
+      })?,
+      None => #default_ts,
+    }
+  }
+}
+
+/// Parses the `#[settings(...)]` attributes on a field, eg: `#[settings(env = "FOO")]`,
+/// `#[settings(default)]` or `#[settings(env = "FOO", default = "42")]`.
+fn parse_field_settings(field: &Field) -> Result<FieldSettings<'_>> {
+  let mut attr_args = AttrArgs::parse(&field.attrs, SETTINGS_ATTR)?;
+  let env = attr_args.take_str("env")?;
+  let default = match attr_args.take_str("default")? {
+    Some(default) => FieldDefault::Str(default),
+    None if attr_args.take_flag("default")? => FieldDefault::Trait,
+    None => FieldDefault::None,
+  };
+  attr_args
+    .finish("unknown setting, expected `env = \"...\"`, `default` or `default = \"...\"`")?;
+
+  Ok(FieldSettings {
+    field,
+    env,
+    default,
+  })
+}
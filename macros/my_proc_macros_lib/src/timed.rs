@@ -0,0 +1,125 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+use quote::quote;
+use syn::{parse::{Parse, ParseStream},
+          parse_macro_input,
+          Error,
+          Ident,
+          ItemFn,
+          LitStr,
+          Result,
+          Token};
+
+const LOG_LEVELS: [&str; 5] = ["trace", "debug", "info", "warn", "error"];
+
+/// See [`TimedArgs`] for more information on the syntax that this macro accepts.
+///
+/// Wraps the annotated fn (sync or async) so that the `log!` macro (resolved at the call
+/// site) is called when the fn starts & when it ends, w/ the module path, the fn name &
+/// the elapsed time, eg: `log!(DEBUG, "{}::{} end, elapsed: {:?}", ...)`.
+///
+/// The end is logged by a guard that is dropped when the body is done, so it is logged no
+/// matter how the fn exits (`return`, `?`, or a panic). For an async fn the guard lives in
+/// its future, so the elapsed time includes the time spent waiting on `.await`s.
+pub fn attrib_proc_macro_impl(
+  args: proc_macro::TokenStream,
+  item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+  let TimedArgs { level_ident } = parse_macro_input!(args as TimedArgs);
+  let ItemFn {
+    attrs,
+    vis,
+    sig,
+    block,
+  } = parse_macro_input!(item as ItemFn);
+
+  let fn_name_str = sig.ident.to_string();
+  let stmts = &block.stmts;
+
+  quote! {
+    #(#attrs)*
+    #vis #sig {
+      let _timed_guard = {
+        struct TimedGuard(std::time::Instant);
+        impl Drop for TimedGuard {
+          fn drop(&mut self) {
+            log!(
+              #level_ident,
+              "{}::{} end, elapsed: {:?}",
+              module_path!(),
+              #fn_name_str,
+              self.0.elapsed()
+            );
+          }
+        }
+        log!(#level_ident, "{}::{} start", module_path!(), #fn_name_str);
+        TimedGuard(std::time::Instant::now())
+      };
+      #(#stmts)*
+    }
+  }
+  .into()
+}
+
+/// Example of syntax to parse:
+/// ```no_run
+/// #[timed]
+/// #[timed(level = "debug")]
+/// ```
+///
+/// The level is one of: `trace`, `debug`, `info` (default), `warn`, `error`. It is passed
+/// to `log!` in uppercase, eg: `DEBUG`.
+struct TimedArgs {
+  level_ident: Ident,
+}
+
+/// [Parse docs](https://docs.rs/syn/latest/syn/parse/index.html)
+impl Parse for TimedArgs {
+  fn parse(input: ParseStream) -> Result<Self> {
+    let mut level_str = LitStr::new("info", input.span());
+
+    // 👀 Optional level, eg: `level = "debug"`.
+    if !input.is_empty() {
+      let key: Ident = input.parse()?;
+      if key != "level" {
+        return Err(Error::new(
+          key.span(),
+          format!("unknown key `{}`, expected `level`", key),
+        ));
+      }
+      input.parse::<Token![=]>()?;
+      level_str = input.parse()?;
+    }
+
+    let level = level_str.value().to_lowercase();
+    if !LOG_LEVELS.contains(&level.as_str()) {
+      return Err(Error::new(
+        level_str.span(),
+        format!(
+          "unknown level `{}`, expected one of: {}",
+          level_str.value(),
+          LOG_LEVELS.join(", ")
+        ),
+      ));
+    }
+
+    Ok(TimedArgs {
+      level_ident: Ident::new(&level.to_uppercase(), level_str.span()),
+    })
+  }
+}
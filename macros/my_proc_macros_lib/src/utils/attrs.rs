@@ -0,0 +1,222 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+//! Parsing for the helper attributes of the derive macros, eg: `#[has_id]` or
+//! `#[settings(env = "FOO", default)]`. Every error is spanned, so it points at the
+//! attribute (or the arg) that is wrong.
+
+#![allow(dead_code)]
+
+use syn::{spanned::Spanned, Attribute, Error, Lit, LitStr, Meta, NestedMeta, Result};
+
+/// Returns the `#[name]` attributes in `attrs`, eg: `#[has_id]`. It is an error for one of
+/// them to have args, eg: `#[has_id(key)]`.
+pub fn get_flag_attrs<'a>(
+  attrs: &'a [Attribute],
+  name: &str,
+) -> Result<Vec<&'a Attribute>> {
+  let mut flag_attr_vec = vec![];
+  for attr in attrs.iter().filter(|it| it.path.is_ident(name)) {
+    if !matches!(attr.parse_meta()?, Meta::Path(_)) {
+      return Err(Error::new(
+        attr.span(),
+        format!("expected `#[{}]`, it does not take any arguments", name),
+      ));
+    }
+    flag_attr_vec.push(attr);
+  }
+  Ok(flag_attr_vec)
+}
+
+/// The args of all the `#[name(...)]` attributes of an item or a field, eg: `env = "FOO"` &
+/// `default` in `#[settings(env = "FOO", default)]`.
+///
+/// Args are taken out by key, using the `take_*` fns, and then [AttrArgs::finish] reports
+/// any that are left (eg: misspelled ones).
+pub struct AttrArgs {
+  nested_meta_vec: Vec<NestedMeta>,
+}
+
+impl AttrArgs {
+  /// Merges the args of all the `#[name(...)]` attributes in `attrs` (in order). There are
+  /// none if there aren't any of those attributes.
+  pub fn parse(
+    attrs: &[Attribute],
+    name: &str,
+  ) -> Result<Self> {
+    let mut nested_meta_vec = vec![];
+    for attr in attrs.iter().filter(|it| it.path.is_ident(name)) {
+      match attr.parse_meta()? {
+        Meta::List(meta_list) => nested_meta_vec.extend(meta_list.nested),
+        meta => {
+          return Err(Error::new(
+            meta.span(),
+            format!("expected `#[{}(...)]`", name),
+          ))
+        }
+      }
+    }
+    Ok(AttrArgs { nested_meta_vec })
+  }
+
+  /// Takes the flag `key`, eg: `skip` in `#[describe(skip)]`.
+  pub fn take_flag(
+    &mut self,
+    key: &str,
+  ) -> Result<bool> {
+    let taken_vec = self.take_all(key, |meta| matches!(meta, Meta::Path(_)))?;
+    Ok(!taken_vec.is_empty())
+  }
+
+  /// Takes the string value of `key`, eg: `"FOO"` in `#[settings(env = "FOO")]`.
+  pub fn take_str(
+    &mut self,
+    key: &str,
+  ) -> Result<Option<LitStr>> {
+    let taken_vec = self.take_all(key, |meta| matches!(meta, Meta::NameValue(_)))?;
+    match taken_vec.into_iter().next() {
+      Some(Meta::NameValue(name_value)) => match name_value.lit {
+        Lit::Str(lit_str) => Ok(Some(lit_str)),
+        lit => Err(Error::new(lit.span(), "expected a string literal, eg: `\"FOO\"`")),
+      },
+      _ => Ok(None),
+    }
+  }
+
+  /// Takes the nested args of `key`, eg: `a = "b"` in `#[serde(rename(a = "b"))]`.
+  pub fn take_list(
+    &mut self,
+    key: &str,
+  ) -> Result<Option<AttrArgs>> {
+    let taken_vec = self.take_all(key, |meta| matches!(meta, Meta::List(_)))?;
+    match taken_vec.into_iter().next() {
+      Some(Meta::List(meta_list)) => Ok(Some(AttrArgs {
+        nested_meta_vec: meta_list.nested.into_iter().collect(),
+      })),
+      _ => Ok(None),
+    }
+  }
+
+  /// Fails (w/ the `expected` message) on the first arg that wasn't taken.
+  pub fn finish(
+    self,
+    expected: &str,
+  ) -> Result<()> {
+    match self.nested_meta_vec.first() {
+      Some(nested_meta) => Err(Error::new(nested_meta.span(), expected)),
+      None => Ok(()),
+    }
+  }
+
+  /// Takes the args named `key` that `is_kind` matches, failing if there's more than 1.
+  fn take_all(
+    &mut self,
+    key: &str,
+    is_kind: fn(&Meta) -> bool,
+  ) -> Result<Vec<Meta>> {
+    let (taken_vec, remaining_vec): (Vec<NestedMeta>, Vec<NestedMeta>) =
+      self.nested_meta_vec.drain(..).partition(|nested_meta| match nested_meta {
+        NestedMeta::Meta(meta) => meta.path().is_ident(key) && is_kind(meta),
+        NestedMeta::Lit(_) => false,
+      });
+    self.nested_meta_vec = remaining_vec;
+
+    if let Some(repeated) = taken_vec.get(1) {
+      return Err(Error::new(
+        repeated.span(),
+        format!("`{}` is repeated", key),
+      ));
+    }
+    Ok(
+      taken_vec
+        .into_iter()
+        .filter_map(|nested_meta| match nested_meta {
+          NestedMeta::Meta(meta) => Some(meta),
+          NestedMeta::Lit(_) => None,
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+fn parse_field_attrs(field_str: &str) -> Vec<Attribute> {
+  let derive_input =
+    syn::parse_str::<syn::DeriveInput>(&format!("struct Foo {{ {} }}", field_str)).unwrap();
+  match derive_input.data {
+    syn::Data::Struct(data_struct) => data_struct
+      .fields
+      .into_iter()
+      .next()
+      .unwrap()
+      .attrs,
+    _ => unreachable!(),
+  }
+}
+
+#[test]
+fn test_get_flag_attrs() {
+  let attrs = parse_field_attrs("#[has_id] #[other(x)] key: usize");
+  assert_eq!(get_flag_attrs(&attrs, "has_id").unwrap().len(), 1);
+  assert!(get_flag_attrs(&attrs, "missing").unwrap().is_empty());
+
+  let attrs = parse_field_attrs("#[has_id(key)] key: usize");
+  assert_eq!(
+    get_flag_attrs(&attrs, "has_id").unwrap_err().to_string(),
+    "expected `#[has_id]`, it does not take any arguments"
+  );
+}
+
+#[test]
+fn test_take_attr_args() {
+  let attrs = parse_field_attrs(
+    r#"#[settings(env = "FOO", default)] #[settings(rename(toml = "bar"))] foo: u8"#,
+  );
+  let mut attr_args = AttrArgs::parse(&attrs, "settings").unwrap();
+  assert_eq!(attr_args.take_str("env").unwrap().unwrap().value(), "FOO");
+  // `default` is a flag, not a string.
+  assert!(attr_args.take_str("default").unwrap().is_none());
+  assert!(attr_args.take_flag("default").unwrap());
+  let mut rename_args = attr_args.take_list("rename").unwrap().unwrap();
+  assert_eq!(rename_args.take_str("toml").unwrap().unwrap().value(), "bar");
+  assert!(rename_args.finish("").is_ok());
+  assert!(attr_args.finish("").is_ok());
+}
+
+#[test]
+fn test_attr_args_errors() {
+  let attrs = parse_field_attrs(r#"#[settings(env = "FOO", env = "BAR")] foo: u8"#);
+  let mut attr_args = AttrArgs::parse(&attrs, "settings").unwrap();
+  assert_eq!(
+    attr_args.take_str("env").unwrap_err().to_string(),
+    "`env` is repeated"
+  );
+
+  let attrs = parse_field_attrs(r#"#[settings(env = 42)] foo: u8"#);
+  let mut attr_args = AttrArgs::parse(&attrs, "settings").unwrap();
+  assert!(attr_args.take_str("env").is_err());
+
+  let attrs = parse_field_attrs(r#"#[settings(required)] foo: u8"#);
+  let attr_args = AttrArgs::parse(&attrs, "settings").unwrap();
+  assert_eq!(
+    attr_args.finish("unknown setting").unwrap_err().to_string(),
+    "unknown setting"
+  );
+
+  let attrs = parse_field_attrs(r#"#[settings] foo: u8"#);
+  assert!(AttrArgs::parse(&attrs, "settings").is_err());
+}
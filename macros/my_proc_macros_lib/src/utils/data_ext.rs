@@ -17,6 +17,7 @@
 
 pub trait DataExt {
   fn is_struct(&self) -> bool;
+  fn is_struct_with_named_fields(&self) -> bool;
 }
 
 impl DataExt for syn::Data {
@@ -26,4 +27,13 @@ impl DataExt for syn::Data {
       _ => false,
     }
   }
+
+  fn is_struct_with_named_fields(&self) -> bool {
+    match self {
+      syn::Data::Struct(data_struct) => {
+        matches!(data_struct.fields, syn::Fields::Named(_))
+      }
+      _ => false,
+    }
+  }
 }
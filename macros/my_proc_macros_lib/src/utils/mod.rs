@@ -22,3 +22,4 @@ pub mod meta_ext;
 pub mod nested_meta_ext;
 pub mod attribute_args_ext;
 pub mod type_ext;
+pub mod attrs;
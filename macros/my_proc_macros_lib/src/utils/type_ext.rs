@@ -15,16 +15,51 @@
  *   limitations under the License.
 */
 
+#![allow(dead_code)]
+
 use syn::{punctuated::Punctuated,
           token::Comma,
           GenericArgument,
           Ident,
           PathArguments::AngleBracketed,
+          PathSegment,
           Type};
 
+/// A small tree that describes the shape of a [syn::Type], eg: `Option<Vec<&T>>` is:
+/// ```text
+/// Path {
+///   ident: Option,
+///   generic_args: [Path { ident: Vec, generic_args: [Reference { .. Path { ident: T } }] }],
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeShape {
+  /// Only the last segment of the path is kept, eg: `std::collections::HashMap<K, V>` is
+  /// `HashMap`, w/ `K` & `V` as its generic args. Lifetime & const args are left out.
+  Path {
+    ident: Ident,
+    generic_args: Vec<TypeShape>,
+  },
+  Reference {
+    is_mut: bool,
+    inner: Box<TypeShape>,
+  },
+  /// `[T]`.
+  Slice(Box<TypeShape>),
+  /// `[T; N]`.
+  Array(Box<TypeShape>),
+  Tuple(Vec<TypeShape>),
+  /// Anything else (eg: `impl Trait` or `fn(T) -> U`), which isn't looked into.
+  Other,
+}
+
 pub trait TypeExt {
   fn has_ident(&self) -> bool;
   fn get_ident(&self) -> Option<Ident>;
+  fn get_last_segment_ident(&self) -> Option<Ident>;
+  fn unwrap_reference(&self) -> &Type;
+  fn unwrap_slice(&self) -> Option<&Type>;
+  fn get_type_shape(&self) -> TypeShape;
   fn has_angle_bracketed_generic_args(&self) -> bool;
   fn get_angle_bracketed_generic_args_result(
     &self
@@ -44,22 +79,85 @@ impl TypeExt for syn::Type {
     }
   }
 
+  /// The ident of the first segment of the path, eg: `std` for `std::vec::Vec<T>`. Use
+  /// [TypeExt::get_last_segment_ident] to get the name of the type itself.
   fn get_ident(&self) -> Option<Ident> {
     match self {
-      Type::Path(ref type_path) => {
-        let path = &type_path.path;
-        let ident = &path
-          .segments
-          .first()
-          .unwrap()
-          .ident;
-        Some(ident.clone())
-      }
+      Type::Path(ref type_path) => type_path
+        .path
+        .segments
+        .first()
+        .map(|segment| segment.ident.clone()),
+      _ => None,
+    }
+  }
+
+  /// The ident of the last segment of the path, eg: `Vec` for `std::vec::Vec<T>`.
+  fn get_last_segment_ident(&self) -> Option<Ident> {
+    get_last_segment(self).map(|segment| segment.ident.clone())
+  }
+
+  /// Strips any `&`, `&mut` (& parens) off the type, eg: `&&mut T` is `T`.
+  fn unwrap_reference(&self) -> &Type {
+    match self {
+      Type::Reference(type_reference) => type_reference.elem.unwrap_reference(),
+      Type::Paren(type_paren) => type_paren.elem.unwrap_reference(),
+      Type::Group(type_group) => type_group.elem.unwrap_reference(),
+      _ => self,
+    }
+  }
+
+  /// The element type of a slice or an array (even behind a reference), eg: `T` for `[T]`,
+  /// `&[T]` & `[T; 4]`.
+  fn unwrap_slice(&self) -> Option<&Type> {
+    match self.unwrap_reference() {
+      Type::Slice(type_slice) => Some(&type_slice.elem),
+      Type::Array(type_array) => Some(&type_array.elem),
       _ => None,
     }
   }
 
-  /// True if self.type_path.path.segments.first().arguments.args.len() to be > 0.
+  fn get_type_shape(&self) -> TypeShape {
+    match self {
+      Type::Path(_) => match get_last_segment(self) {
+        Some(segment) => TypeShape::Path {
+          ident: segment.ident.clone(),
+          generic_args: match &segment.arguments {
+            AngleBracketed(angle_bracketed_generic_arguments) => {
+              angle_bracketed_generic_arguments
+                .args
+                .iter()
+                .filter_map(|generic_arg| match generic_arg {
+                  GenericArgument::Type(type_arg) => Some(type_arg.get_type_shape()),
+                  _ => None,
+                })
+                .collect()
+            }
+            _ => vec![],
+          },
+        },
+        None => TypeShape::Other,
+      },
+      Type::Reference(type_reference) => TypeShape::Reference {
+        is_mut: type_reference.mutability.is_some(),
+        inner: Box::new(type_reference.elem.get_type_shape()),
+      },
+      Type::Slice(type_slice) => TypeShape::Slice(Box::new(type_slice.elem.get_type_shape())),
+      Type::Array(type_array) => TypeShape::Array(Box::new(type_array.elem.get_type_shape())),
+      Type::Tuple(type_tuple) => TypeShape::Tuple(
+        type_tuple
+          .elems
+          .iter()
+          .map(|elem| elem.get_type_shape())
+          .collect(),
+      ),
+      Type::Paren(type_paren) => type_paren.elem.get_type_shape(),
+      Type::Group(type_group) => type_group.elem.get_type_shape(),
+      _ => TypeShape::Other,
+    }
+  }
+
+  /// True if self.type_path.path.segments.last().arguments.args.len() to be > 0.
   fn has_angle_bracketed_generic_args(&self) -> bool {
     match self.get_angle_bracketed_generic_args_result() {
       Ok(generic_args) => generic_args.len() > 0,
@@ -67,19 +165,13 @@ impl TypeExt for syn::Type {
     }
   }
 
-  /// Ok if self.type_path.path.segments.first().arguments.args exists.
+  /// Ok if self.type_path.path.segments.last().arguments.args exists. The last segment is
+  /// the one that holds them, eg: `std::vec::Vec<T>`.
   fn get_angle_bracketed_generic_args_result(
     &self
   ) -> Result<Punctuated<GenericArgument, Comma>, ()> {
-    if let Type::Path(ref type_path) = self {
-      let path = &type_path.path;
-      let path_arguments = &path
-        .segments
-        .first()
-        .unwrap()
-        .arguments;
-
-      if let AngleBracketed(ref angle_bracketed_generic_arguments) = path_arguments {
+    if let Some(segment) = get_last_segment(self) {
+      if let AngleBracketed(ref angle_bracketed_generic_arguments) = segment.arguments {
         return Ok(
           angle_bracketed_generic_arguments
             .args
@@ -98,14 +190,8 @@ impl TypeExt for syn::Type {
         for generic_arg in generic_args {
           match generic_arg {
             GenericArgument::Type(ref type_arg) => {
-              if let Type::Path(ref type_path) = type_arg {
-                let path = &type_path.path;
-                let ident = &path
-                  .segments
-                  .first()
-                  .unwrap()
-                  .ident;
-                idents.push(ident.clone());
+              if let Some(ident) = type_arg.get_last_segment_ident() {
+                idents.push(ident);
               }
             }
             _ => {}
@@ -117,3 +203,88 @@ impl TypeExt for syn::Type {
     }
   }
 }
+
+fn get_last_segment(ty: &Type) -> Option<&PathSegment> {
+  match ty {
+    Type::Path(ref type_path) => type_path.path.segments.last(),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+fn parse_type(ty_str: &str) -> Type { syn::parse_str::<Type>(ty_str).unwrap() }
+
+#[cfg(test)]
+fn path_shape(
+  ident_str: &str,
+  generic_args: Vec<TypeShape>,
+) -> TypeShape {
+  TypeShape::Path {
+    ident: Ident::new(ident_str, proc_macro2::Span::call_site()),
+    generic_args,
+  }
+}
+
+#[test]
+fn test_get_ident_and_last_segment_ident() {
+  let ty = parse_type("std::collections::HashMap<K, V>");
+  assert_eq!(ty.get_ident().unwrap(), "std");
+  assert_eq!(ty.get_last_segment_ident().unwrap(), "HashMap");
+  assert_eq!(
+    ty.get_angle_bracketed_generic_args_idents_result(),
+    Ok(vec![
+      Ident::new("K", proc_macro2::Span::call_site()),
+      Ident::new("V", proc_macro2::Span::call_site()),
+    ])
+  );
+
+  let ty = parse_type("&str");
+  assert!(ty.get_ident().is_none());
+  assert!(ty.get_last_segment_ident().is_none());
+  assert_eq!(ty.unwrap_reference().get_last_segment_ident().unwrap(), "str");
+}
+
+#[test]
+fn test_unwrap_reference_and_slice() {
+  let ty = parse_type("&&mut (Vec<u8>)");
+  assert_eq!(ty.unwrap_reference().get_last_segment_ident().unwrap(), "Vec");
+
+  for ty_str in ["[u8]", "&[u8]", "&mut [u8; 4]"] {
+    let elem_ty = parse_type(ty_str).unwrap_slice().unwrap().clone();
+    assert_eq!(elem_ty, parse_type("u8"));
+  }
+  assert!(parse_type("Vec<u8>").unwrap_slice().is_none());
+}
+
+#[test]
+fn test_get_type_shape() {
+  assert_eq!(
+    parse_type("Option<Vec<T>>").get_type_shape(),
+    path_shape("Option", vec![path_shape("Vec", vec![path_shape("T", vec![])])])
+  );
+
+  assert_eq!(
+    parse_type("std::collections::HashMap<&'a str, [u8; 4]>").get_type_shape(),
+    path_shape(
+      "HashMap",
+      vec![
+        TypeShape::Reference {
+          is_mut: false,
+          inner: Box::new(path_shape("str", vec![])),
+        },
+        TypeShape::Array(Box::new(path_shape("u8", vec![]))),
+      ]
+    )
+  );
+
+  assert_eq!(
+    parse_type("(&mut [T], fn())").get_type_shape(),
+    TypeShape::Tuple(vec![
+      TypeShape::Reference {
+        is_mut: true,
+        inner: Box::new(TypeShape::Slice(Box::new(path_shape("T", vec![])))),
+      },
+      TypeShape::Other,
+    ])
+  );
+}
@@ -0,0 +1,14 @@
+// The report is emitted as a `deprecated` warning, which is denied here so that the
+// snapshot can check it.
+#![deny(deprecated)]
+
+use my_proc_macros_lib::fn_macro_ast_viz_debug;
+
+fn_macro_ast_viz_debug! {
+  report = warning;
+  fn foo() -> u32 { 42 }
+}
+
+fn main() {
+  assert_eq!(foo(), 42);
+}
@@ -0,0 +1,9 @@
+use my_proc_macros_lib::Builder;
+
+#[derive(Builder)]
+enum Direction {
+  Up,
+  Down,
+}
+
+fn main() {}
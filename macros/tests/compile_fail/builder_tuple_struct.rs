@@ -0,0 +1,6 @@
+use my_proc_macros_lib::Builder;
+
+#[derive(Builder)]
+struct Point(i32, i32);
+
+fn main() {}
@@ -0,0 +1,21 @@
+#![allow(dead_code)]
+
+use my_proc_macros_lib::Describe;
+
+trait Describe {
+  fn describe() -> String;
+}
+
+struct FieldMeta {
+  name: &'static str,
+  type_name: &'static str,
+  index: usize,
+}
+
+#[derive(Describe)]
+union Number {
+  int: u32,
+  float: f32,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+#![allow(dead_code)]
+
+use my_proc_macros_lib::Describe;
+
+trait Describe {
+  fn describe() -> String;
+}
+
+#[derive(Describe)]
+#[describe(field)]
+struct Point {
+  x: i32,
+  y: i32,
+}
+
+fn main() {}
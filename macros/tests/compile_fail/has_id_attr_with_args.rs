@@ -0,0 +1,14 @@
+use my_proc_macros_lib::HasId;
+
+trait HasId {
+  type Id;
+  fn id(&self) -> &Self::Id;
+}
+
+#[derive(HasId)]
+struct Payload {
+  #[has_id(key)]
+  key: usize,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use my_proc_macros_lib::HasId;
+
+trait HasId {
+  type Id;
+  fn id(&self) -> &Self::Id;
+}
+
+#[derive(HasId)]
+struct Payload {
+  #[has_id]
+  key: usize,
+  #[has_id]
+  other_key: usize,
+}
+
+fn main() {}
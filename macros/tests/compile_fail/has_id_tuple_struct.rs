@@ -0,0 +1,11 @@
+use my_proc_macros_lib::HasId;
+
+trait HasId {
+  type Id;
+  fn id(&self) -> &Self::Id;
+}
+
+#[derive(HasId)]
+struct Payload(usize);
+
+fn main() {}
@@ -0,0 +1,21 @@
+use my_proc_macros_lib::keymap;
+
+struct KeyBinding<A> {
+  chords: Vec<KeyChord>,
+  action: A,
+}
+
+struct KeyChord {
+  key: &'static str,
+  ctrl: bool,
+  alt: bool,
+  shift: bool,
+}
+
+fn main() {
+  let _ = keymap! {
+    "ctrl+shift+p" => 1,
+    "g g" => 2,
+    "ctrl+pgup" => 3,
+  };
+}
@@ -0,0 +1,9 @@
+use my_proc_macros_lib::Settings;
+
+#[derive(Settings)]
+struct AppSettings {
+  #[settings(env = 8080)]
+  port: u16,
+}
+
+fn main() {}
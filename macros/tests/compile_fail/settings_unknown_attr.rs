@@ -0,0 +1,9 @@
+use my_proc_macros_lib::Settings;
+
+#[derive(Settings)]
+struct AppSettings {
+  #[settings(env = "APP_NAME", required)]
+  name: String,
+}
+
+fn main() {}
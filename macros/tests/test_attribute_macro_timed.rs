@@ -0,0 +1,115 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+#![allow(dead_code)]
+
+//! # Watch macro expansion
+//!
+//! To watch for changes run this script:
+//! `./cargo-watch-macro-expand-one-test.fish test_attribute_macro_timed`
+//!
+//! # Watch test output
+//!
+//! To watch for test output run this script:
+//! `./cargo-watch-one-test.fish test_attribute_macro_timed`
+
+use std::cell::RefCell;
+
+use my_proc_macros_lib::timed;
+
+thread_local! {
+  static LOG_LINES: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+/// Stand-in for the `log!` macro that the generated code calls. It records each line.
+macro_rules! log {
+  ($level:ident, $($arg:tt)*) => {
+    LOG_LINES.with(|it| {
+      it.borrow_mut()
+        .push(format!("{} {}", stringify!($level), format!($($arg)*)))
+    })
+  };
+}
+
+fn take_log_lines() -> Vec<String> { LOG_LINES.with(|it| it.borrow_mut().drain(..).collect()) }
+
+fn assert_start_and_end(
+  log_lines: &[String],
+  level: &str,
+  fn_name: &str,
+) {
+  assert_eq!(log_lines.len(), 2);
+  assert_eq!(
+    log_lines[0],
+    format!("{} {}::{} start", level, module_path!(), fn_name)
+  );
+  assert!(log_lines[1].starts_with(&format!(
+    "{} {}::{} end, elapsed: ",
+    level,
+    module_path!(),
+    fn_name
+  )));
+}
+
+#[test]
+fn test_attribute_macro_timed_default_level() {
+  #[timed]
+  fn add(
+    a: i32,
+    b: i32,
+  ) -> i32 {
+    a + b
+  }
+
+  assert_eq!(add(1, 2), 3);
+  assert_start_and_end(&take_log_lines(), "INFO", "add");
+}
+
+#[test]
+fn test_attribute_macro_timed_early_return() {
+  #[timed(level = "debug")]
+  fn parse(input: &str) -> Result<i32, std::num::ParseIntError> {
+    if input.is_empty() {
+      return Ok(0);
+    }
+    let value: i32 = input.parse()?;
+    Ok(value * 2)
+  }
+
+  assert_eq!(parse(""), Ok(0));
+  assert_start_and_end(&take_log_lines(), "DEBUG", "parse");
+
+  assert!(parse("not a number").is_err());
+  assert_start_and_end(&take_log_lines(), "DEBUG", "parse");
+}
+
+#[tokio::test]
+async fn test_attribute_macro_timed_async() {
+  #[timed(level = "warn")]
+  async fn delayed_add(
+    a: i32,
+    b: i32,
+  ) -> i32 {
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    a + b
+  }
+
+  let future = delayed_add(1, 2);
+  assert!(take_log_lines().is_empty());
+  assert_eq!(future.await, 3);
+  assert_start_and_end(&take_log_lines(), "WARN", "delayed_add");
+}
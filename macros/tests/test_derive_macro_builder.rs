@@ -84,3 +84,9 @@ fn test_proc_macro_generics() {
   assert_eq!(my_pt.x, 1);
   assert_eq!(my_pt.y, 2);
 }
+
+#[test]
+fn test_derive_macro_builder_compile_fail() {
+  let test_cases = trybuild::TestCases::new();
+  test_cases.compile_fail("tests/compile_fail/builder_*.rs");
+}
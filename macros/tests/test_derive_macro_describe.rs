@@ -33,6 +33,13 @@ trait Describe {
     fn describe() -> String;
 }
 
+#[derive(Debug, PartialEq)]
+struct FieldMeta {
+  name: &'static str,
+  type_name: &'static str,
+  index: usize,
+}
+
 #[test]
 fn test_proc_macro() {
   #[derive(Describe)]
@@ -71,3 +78,100 @@ fn test_proc_macro_generics() {
     "Point is a struct with these named fields: x, y"
   );
 }
+
+#[test]
+fn test_proc_macro_field_metadata() {
+  #[derive(Describe)]
+  #[describe(fields)]
+  struct MyStruct<'a, T> {
+    my_string: String,
+    my_vec: Vec<Vec<T>>,
+    my_ref: &'a str,
+  }
+
+  assert_eq!(
+    MyStruct::<i32>::field_names(),
+    &["my_string", "my_vec", "my_ref"]
+  );
+  assert_eq!(
+    MyStruct::<i32>::FIELDS,
+    &[
+      FieldMeta {
+        name: "my_string",
+        type_name: "String",
+        index: 0,
+      },
+      FieldMeta {
+        name: "my_vec",
+        type_name: "Vec<Vec<T>>",
+        index: 1,
+      },
+      FieldMeta {
+        name: "my_ref",
+        type_name: "&'a str",
+        index: 2,
+      },
+    ]
+  );
+}
+
+#[test]
+fn test_proc_macro_field_metadata_unnamed_unit_and_enum() {
+  #[derive(Describe)]
+  #[describe(fields)]
+  struct MyTuple(u8, Option<String>);
+
+  #[derive(Describe)]
+  #[describe(fields)]
+  struct MyUnit;
+
+  #[derive(Describe)]
+  #[describe(fields)]
+  enum MyEnum {
+    MyVariant1,
+  }
+
+  assert_eq!(MyTuple::field_names(), &["0", "1"]);
+  assert_eq!(MyTuple::FIELDS[1].type_name, "Option<String>");
+  assert!(MyUnit::FIELDS.is_empty());
+  assert!(MyEnum::field_names().is_empty());
+}
+
+#[test]
+fn test_proc_macro_generics_w_inline_bounds_and_lifetimes() {
+  #[derive(Describe)]
+  #[describe(fields)]
+  struct Borrowed<'a, T: std::fmt::Display + 'a> {
+    value: &'a T,
+  }
+
+  assert_eq!(
+    Borrowed::<i32>::describe(),
+    "Borrowed is a struct with these named fields: value"
+  );
+  assert_eq!(Borrowed::<i32>::FIELDS[0].type_name, "&'a T");
+}
+
+/// W/out `#[describe(fields)]`, `FieldMeta` doesn't have to be in scope.
+mod without_fields_attr {
+  use super::Describe;
+
+  #[derive(Describe)]
+  pub struct MyStruct {
+    pub my_number: i32,
+  }
+}
+
+#[test]
+fn test_proc_macro_without_fields_attr() {
+  assert_eq!(
+    without_fields_attr::MyStruct::describe(),
+    "MyStruct is a struct with these named fields: my_number"
+  );
+}
+
+#[test]
+fn test_derive_macro_describe_compile_fail() {
+  let test_cases = trybuild::TestCases::new();
+  test_cases.compile_fail("tests/compile_fail/describe_*.rs");
+}
@@ -0,0 +1,72 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+#![allow(dead_code)]
+
+//! # Watch macro expansion
+//!
+//! To watch for changes run this script:
+//! `./cargo-watch-macro-expand-one-test.fish test_derive_macro_has_id`
+//!
+//! # Watch test output
+//!
+//! To watch for test output run this script:
+//! `./cargo-watch-one-test.fish test_derive_macro_has_id`
+
+use my_proc_macros_lib::HasId;
+
+trait HasId {
+  type Id;
+  fn id(&self) -> &Self::Id;
+}
+
+#[test]
+fn test_derive_macro_has_id_default_field() {
+  #[derive(HasId)]
+  struct Payload {
+    id: usize,
+    name: String,
+  }
+
+  let payload = Payload {
+    id: 42,
+    name: "foo".to_string(),
+  };
+  assert_eq!(payload.id(), &42);
+}
+
+#[test]
+fn test_derive_macro_has_id_marked_field() {
+  #[derive(HasId)]
+  struct Payload<T> {
+    id: usize,
+    #[has_id]
+    key: T,
+  }
+
+  let payload = Payload {
+    id: 42,
+    key: "key".to_string(),
+  };
+  assert_eq!(payload.id(), "key");
+}
+
+#[test]
+fn test_derive_macro_has_id_compile_fail() {
+  let test_cases = trybuild::TestCases::new();
+  test_cases.compile_fail("tests/compile_fail/has_id_*.rs");
+}
@@ -0,0 +1,112 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+//! # Watch macro expansion
+//!
+//! To watch for changes run this script:
+//! `./cargo-watch-macro-expand-one-test.fish test_derive_macro_settings`
+//!
+//! # Watch test output
+//!
+//! To watch for test output run this script:
+//! `./cargo-watch-one-test.fish test_derive_macro_settings`
+
+use std::{env, fs, path::PathBuf};
+
+use my_proc_macros_lib::Settings;
+
+#[derive(Debug, PartialEq, Settings)]
+struct AppSettings {
+  #[settings(env = "TEST_DERIVE_MACRO_SETTINGS_NAME")]
+  name: String,
+  #[settings(env = "TEST_DERIVE_MACRO_SETTINGS_PORT", default = "8080")]
+  port: u16,
+  #[settings(default)]
+  verbose: bool,
+  ratio: f32,
+}
+
+/// Each test uses its own file, since tests run in parallel.
+fn write_toml_file(
+  file_name: &str,
+  content: &str,
+) -> PathBuf {
+  let toml_path = env::temp_dir().join(format!("{}-{}", std::process::id(), file_name));
+  fs::write(&toml_path, content).unwrap();
+  toml_path
+}
+
+#[test]
+fn test_settings_priority() {
+  let toml_path = write_toml_file(
+    "priority.toml",
+    "name = \"from toml\"\nport = 1\nverbose = true\nratio = 0.5\n",
+  );
+
+  // TOML file, w/ non string values.
+  let settings = AppSettings::load(Some(&toml_path), &[]).unwrap();
+  assert_eq!(
+    settings,
+    AppSettings {
+      name: "from toml".to_string(),
+      port: 1,
+      verbose: true,
+      ratio: 0.5,
+    }
+  );
+
+  // Env var beats the TOML file, & overrides beat both.
+  env::set_var("TEST_DERIVE_MACRO_SETTINGS_NAME", "from env");
+  let settings = AppSettings::load(Some(&toml_path), &[("ratio", "2")]).unwrap();
+  env::remove_var("TEST_DERIVE_MACRO_SETTINGS_NAME");
+  assert_eq!(settings.name, "from env");
+  assert_eq!(settings.ratio, 2.0);
+
+  let settings =
+    AppSettings::load(Some(&toml_path), &[("name", "from overrides")]).unwrap();
+  assert_eq!(settings.name, "from overrides");
+
+  fs::remove_file(toml_path).unwrap();
+}
+
+#[test]
+fn test_settings_defaults_and_errors() {
+  let settings = AppSettings::load(None, &[("name", "app"), ("ratio", "1.5")]).unwrap();
+  assert_eq!(settings.port, 8080);
+  assert!(!settings.verbose);
+
+  assert_eq!(
+    AppSettings::load(None, &[("name", "app")]).unwrap_err(),
+    "Setting `ratio` is missing, set `ratio` in the TOML file"
+  );
+
+  let toml_path = write_toml_file("errors.toml", "port = \"http\"\nratio = 1.0\n");
+  assert_eq!(
+    AppSettings::load(Some(&toml_path), &[("name", "app")]).unwrap_err(),
+    format!(
+      "Setting `port` is invalid, \"http\" (from {}): invalid digit found in string",
+      toml_path.display()
+    )
+  );
+  fs::remove_file(toml_path).unwrap();
+}
+
+#[test]
+fn test_derive_macro_settings_compile_fail() {
+  let test_cases = trybuild::TestCases::new();
+  test_cases.compile_fail("tests/compile_fail/settings_*.rs");
+}
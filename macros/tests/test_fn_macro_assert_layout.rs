@@ -0,0 +1,104 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+#![allow(dead_code)]
+
+//! # Watch macro expansion
+//!
+//! To watch for changes run this script:
+//! `./cargo-watch-macro-expand-one-test.fish test_fn_macro_assert_layout`
+//!
+//! # Watch test output
+//!
+//! To watch for test output run this script:
+//! `./cargo-watch-one-test.fish test_fn_macro_assert_layout`
+
+use my_proc_macros_lib::assert_layout;
+
+// Minimal stand-ins for the layout types that the macro expects to be in scope.
+
+#[derive(Debug, PartialEq)]
+enum Direction {
+  Horizontal,
+  Vertical,
+}
+
+#[derive(Debug)]
+struct Position {
+  x: u16,
+  y: u16,
+}
+
+#[derive(Debug)]
+struct Size {
+  width: u16,
+  height: u16,
+}
+
+#[derive(Debug)]
+struct Layout {
+  id: String,
+  dir: Direction,
+  origin_pos: Position,
+  bounds_size: Size,
+}
+
+fn make_layout() -> Layout {
+  Layout {
+    id: "col_2".to_string(),
+    dir: Direction::Vertical,
+    origin_pos: Position { x: 250, y: 0 },
+    bounds_size: Size {
+      width: 250,
+      height: 500,
+    },
+  }
+}
+
+#[test]
+fn test_fn_macro_assert_layout() {
+  let layout = make_layout();
+  let half_width = 250;
+
+  assert_layout! {
+    layout,
+    id: "col_2",
+    origin: (half_width, 0),
+    bounds: (half_width, 500),
+    dir: Vertical,
+  }
+
+  // Only some of the fields.
+  assert_layout!(layout, dir: Vertical);
+
+  // Layout is borrowed, not moved.
+  assert_eq!(layout.id, "col_2");
+}
+
+#[test]
+#[should_panic(expected = "layout `col_2` has unexpected `bounds`, layout: Layout {")]
+fn test_fn_macro_assert_layout_failure_message() {
+  assert_layout!(make_layout(), id: "col_2", bounds: (500, 500));
+}
+
+#[test]
+#[should_panic(expected = "layout `col_2` has unexpected `id`")]
+fn test_fn_macro_assert_layout_does_not_shadow_caller_layout() {
+  let mut layout = make_layout();
+  layout.id = "col_1".to_string();
+  assert_layout!(make_layout(), id: layout.id);
+}
@@ -19,6 +19,38 @@ use my_proc_macros_lib::fn_macro_ast_viz_debug;
 
 #[test]
 fn test_proc_macro() {
-  fn_macro_ast_viz_debug!();
+  fn_macro_ast_viz_debug! {
+    fn foo() -> u32 { 42 }
+  }
   assert_eq!(foo(), 42);
 }
+
+#[test]
+fn test_proc_macro_const_report() {
+  fn_macro_ast_viz_debug! {
+    report = const ADD_AST_REPORT;
+    #[inline]
+    pub fn add<T: std::ops::Add<Output = T>>(a: T, b: T) -> T {
+      println!("adding");
+      a + b
+    }
+  }
+  assert_eq!(add(1, 2), 3);
+  assert_eq!(
+    ADD_AST_REPORT,
+    "  ident: add
+  vis: public
+  attrs: 1 [inline]
+  generics: 1 [T : std :: ops :: Add < Output = T >]
+  args: 2 [a : T, b : T]
+  return_type: T
+  stmts: 2
+"
+  );
+}
+
+#[test]
+fn test_proc_macro_warning_report() {
+  let test_cases = trybuild::TestCases::new();
+  test_cases.compile_fail("tests/compile_fail/ast_viz_debug_*.rs");
+}
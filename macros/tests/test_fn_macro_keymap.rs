@@ -0,0 +1,109 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+//! # Watch macro expansion
+//!
+//! To watch for changes run this script:
+//! `./cargo-watch-macro-expand-one-test.fish test_fn_macro_keymap`
+//!
+//! # Watch test output
+//!
+//! To watch for test output run this script:
+//! `./cargo-watch-one-test.fish test_fn_macro_keymap`
+
+use my_proc_macros_lib::keymap;
+
+// Minimal stand-ins for the keymap types that the macro expects to be in scope.
+
+#[derive(Debug, PartialEq)]
+struct KeyBinding<A> {
+  chords: Vec<KeyChord>,
+  action: A,
+}
+
+#[derive(Debug, PartialEq)]
+struct KeyChord {
+  key: &'static str,
+  ctrl: bool,
+  alt: bool,
+  shift: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum Action {
+  OpenPalette,
+  GoTop,
+  Insert(char),
+}
+
+fn chord(
+  key: &'static str,
+  ctrl: bool,
+  alt: bool,
+  shift: bool,
+) -> KeyChord {
+  KeyChord {
+    key,
+    ctrl,
+    alt,
+    shift,
+  }
+}
+
+#[test]
+fn test_keymap_expands_into_bindings() {
+  let binding_vec = keymap! {
+    "shift+ctrl+p" => Action::OpenPalette,
+    "g g" => Action::GoTop,
+    "alt++" => Action::Insert('+'),
+    "f12" => Action::Insert('!'),
+  };
+
+  assert_eq!(
+    binding_vec,
+    vec![
+      KeyBinding {
+        chords: vec![chord("p", true, false, true)],
+        action: Action::OpenPalette,
+      },
+      KeyBinding {
+        chords: vec![chord("g", false, false, false), chord("g", false, false, false)],
+        action: Action::GoTop,
+      },
+      KeyBinding {
+        chords: vec![chord("+", false, true, false)],
+        action: Action::Insert('+'),
+      },
+      KeyBinding {
+        chords: vec![chord("f12", false, false, false)],
+        action: Action::Insert('!'),
+      },
+    ]
+  );
+}
+
+#[test]
+fn test_keymap_can_be_empty() {
+  let binding_vec: Vec<KeyBinding<Action>> = keymap! {};
+  assert!(binding_vec.is_empty());
+}
+
+#[test]
+fn test_fn_macro_keymap_compile_fail() {
+  let test_cases = trybuild::TestCases::new();
+  test_cases.compile_fail("tests/compile_fail/keymap_*.rs");
+}
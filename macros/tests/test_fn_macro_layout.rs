@@ -0,0 +1,213 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+
+ *   http://www.apache.org/licenses/LICENSE-2.0
+
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+*/
+
+#![allow(dead_code)]
+
+//! # Watch macro expansion
+//!
+//! To watch for changes run this script:
+//! `./cargo-watch-macro-expand-one-test.fish test_fn_macro_layout`
+//!
+//! # Watch test output
+//!
+//! To watch for test output run this script:
+//! `./cargo-watch-one-test.fish test_fn_macro_layout`
+
+use my_proc_macros_lib::layout;
+
+// Minimal stand-ins for the layout types that the macro expects to be in scope.
+
+#[derive(Debug)]
+enum Direction {
+  Horizontal,
+  Vertical,
+}
+
+struct Pair {
+  first: u8,
+  second: u8,
+}
+
+impl Pair {
+  fn new(
+    first: u8,
+    second: u8,
+  ) -> Self {
+    Self { first, second }
+  }
+}
+
+#[derive(Debug)]
+struct RequestedSizePercent {
+  width: u8,
+  height: u8,
+}
+
+impl RequestedSizePercent {
+  fn parse_pair(pair: Pair) -> Result<Self, String> {
+    match pair.first > 100 || pair.second > 100 {
+      true => Err(format!("invalid percent: {}, {}", pair.first, pair.second)),
+      false => Ok(Self {
+        width: pair.first,
+        height: pair.second,
+      }),
+    }
+  }
+}
+
+struct LayoutProps {
+  id: String,
+  dir: Direction,
+  req_size: RequestedSizePercent,
+  styles: Option<Vec<&'static str>>,
+}
+
+/// Records every call made to it, so the expansion can be checked.
+#[derive(Default)]
+struct Canvas {
+  calls: Vec<String>,
+}
+
+impl Canvas {
+  fn start_layout(
+    &mut self,
+    props: LayoutProps,
+  ) -> Result<(), String> {
+    self.calls.push(format!(
+      "start {} {:?} {}x{} {:?}",
+      props.id, props.dir, props.req_size.width, props.req_size.height, props.styles
+    ));
+    Ok(())
+  }
+
+  fn end_layout(&mut self) -> Result<(), String> {
+    self.calls.push("end".to_string());
+    Ok(())
+  }
+
+  fn print(
+    &mut self,
+    text_vec: Vec<&str>,
+  ) -> Result<(), String> {
+    self.calls.push(format!("print {}", text_vec.join(" | ")));
+    Ok(())
+  }
+}
+
+#[test]
+fn test_fn_macro_layout_nested() -> Result<(), String> {
+  let mut canvas = Canvas::default();
+
+  layout! {
+    canvas =>
+    {
+      id: "container",
+      dir: Horizontal,
+      size: (100, 100),
+      children: [
+        {
+          id: "col_1",
+          dir: Vertical,
+          size: (50, 100),
+          styles: ["style1"],
+          text: ["col 1 - Hello", "col 1 - World"],
+        },
+        { id: "col_2", dir: Vertical, size: (50, 100), text: ["col 2 - Hello"] },
+      ],
+    }
+  }
+
+  assert_eq!(
+    canvas.calls,
+    vec![
+      "start container Horizontal 100x100 None",
+      "start col_1 Vertical 50x100 Some([\"style1\"])",
+      "print col 1 - Hello | col 1 - World",
+      "end",
+      "start col_2 Vertical 50x100 None",
+      "print col 2 - Hello",
+      "end",
+      "end",
+    ]
+  );
+
+  Ok(())
+}
+
+#[test]
+fn test_fn_macro_layout_siblings_and_exprs() -> Result<(), String> {
+  let mut canvas = Canvas::default();
+  let canvas_ref = &mut canvas;
+  let footer_id = "footer";
+  let half = 50;
+
+  layout! {
+    *canvas_ref =>
+    { id: "header", dir: Horizontal, size: (100, half) },
+    { id: footer_id, dir: Horizontal, size: (100, 100 - half) },
+  }
+
+  assert_eq!(
+    canvas.calls,
+    vec![
+      "start header Horizontal 100x50 None",
+      "end",
+      "start footer Horizontal 100x50 None",
+      "end",
+    ]
+  );
+
+  Ok(())
+}
+
+#[test]
+fn test_fn_macro_layout_propagates_errors() {
+  fn make_layout(canvas: &mut Canvas) -> Result<(), String> {
+    layout! {
+      *canvas =>
+      { id: "container", dir: Vertical, size: (200, 100) }
+    }
+    Ok(())
+  }
+
+  let mut canvas = Canvas::default();
+  assert_eq!(
+    make_layout(&mut canvas),
+    Err("invalid percent: 200, 100".to_string())
+  );
+  assert!(canvas.calls.is_empty());
+}
+
+#[test]
+fn test_fn_macro_layout_evaluates_manager_expr_once() -> Result<(), String> {
+  let mut canvas = Canvas::default();
+  let mut eval_count = 0;
+
+  layout! {
+    {
+      eval_count += 1;
+      &mut canvas
+    } =>
+    { id: "header", dir: Horizontal, size: (100, 50), text: ["Hello"] },
+    { id: "footer", dir: Horizontal, size: (100, 50) },
+  }
+
+  assert_eq!(eval_count, 1);
+  assert_eq!(canvas.calls.len(), 5);
+
+  Ok(())
+}
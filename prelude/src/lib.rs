@@ -0,0 +1,46 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+//! Umbrella crate for this repo. Instead of depending on `r3bl_rs_utils`,
+//! `my_proc_macros_lib` & `graphemes` separately (and keeping their versions in sync), you
+//! can depend on this crate and either use the individual modules below, or import
+//! everything at once via [`prelude`].
+
+/// Tree arena: `Arena`, `MTArena`, etc.
+pub mod arena {
+  pub use r3bl_rs_utils::tree_memory_arena::*;
+}
+
+/// Proc macros: `layout!`, `#[derive(Describe)]`, `#[derive(Builder)]`, etc.
+pub mod macros {
+  pub use my_proc_macros_lib::*;
+}
+
+/// Grapheme cluster aware strings: `UnicodeStringExt`, `UnicodeString`, etc.
+pub mod graphemes {
+  pub use ::graphemes::*;
+}
+
+/// TUI layout & crossterm APIs.
+pub mod tui {
+  pub use r3bl_rs_utils::tui::*;
+}
+
+/// `use rust_scratch_prelude::prelude::*;` to import everything in this crate.
+pub mod prelude {
+  pub use super::{arena::*, graphemes::*, macros::*, tui::*};
+}
@@ -0,0 +1,38 @@
+/*
+ *   Copyright (c) 2022 Nazmul Idris
+ *   All rights reserved.
+ *
+ *   Licensed under the Apache License, Version 2.0 (the "License");
+ *   you may not use this file except in compliance with the License.
+ *   You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *   Unless required by applicable law or agreed to in writing, software
+ *   distributed under the License is distributed on an "AS IS" BASIS,
+ *   WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *   See the License for the specific language governing permissions and
+ *   limitations under the License.
+ */
+
+use rust_scratch_prelude::prelude::*;
+
+trait Describe {
+  fn describe() -> String;
+}
+
+#[test]
+fn test_prelude_re_exports() {
+  // From `graphemes`.
+  let string = "Hi 📦".to_string();
+  let u_s = string.unicode_string();
+  assert_eq!(u_s.grapheme_cluster_segment_count, 4);
+
+  // From `my_proc_macros_lib`.
+  #[derive(Describe)]
+  struct MyStruct;
+  assert_eq!(MyStruct::describe(), "MyStruct is a unit struct");
+
+  // From `r3bl_rs_utils`.
+  let _arena = Arena::<usize>::new();
+}
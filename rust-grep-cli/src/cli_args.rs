@@ -0,0 +1,607 @@
+//! A small argument parsing layer: subcommands, flag validation & generated `--help` /
+//! `--version` output. It only knows about the [CommandSpec] it is given (& nothing else in
+//! this crate), so other binaries can reuse it by declaring their own spec.
+//!
+//! Parsing the values of the flags into typed options is left to the `extract_from`
+//! functions of each option type (eg: `ContextOptions::extract_from`). This layer makes
+//! sure that any flag that reaches them is known, & that it has a value if it needs one.
+
+use std::{error::Error, fmt};
+
+/// A command (or a subcommand) & the flags that it accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandSpec {
+  pub name: &'static str,
+  pub about: &'static str,
+  /// The positional args, eg: `<search-string> [path-to-file]`.
+  pub usage: &'static str,
+  pub flags: &'static [FlagSpec],
+  /// The first subcommand is the default one, used when the first arg isn't the name of a
+  /// subcommand.
+  pub subcommands: &'static [CommandSpec],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlagSpec {
+  /// Eg: `--context`.
+  pub long: &'static str,
+  /// Eg: `-C`.
+  pub short: Option<&'static str>,
+  /// The name of the value that the flag takes, if it takes one, eg: `n`. The value can be
+  /// passed as `--context=2` or `--context 2`.
+  pub value_name: Option<&'static str>,
+  pub help: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliAction {
+  /// `args` still starts w/ the program name, but the subcommand name is removed from it.
+  Run {
+    subcommand: &'static CommandSpec,
+    args: Vec<String>,
+  },
+  PrintHelp(String),
+  PrintVersion(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+  UnknownFlag {
+    flag: String,
+    command: String,
+    suggestion: Option<&'static str>,
+  },
+  MissingValue {
+    flag: String,
+    value_name: &'static str,
+  },
+  UnexpectedValue {
+    flag: String,
+  },
+}
+
+impl fmt::Display for CliError {
+  fn fmt(
+    &self,
+    f: &mut fmt::Formatter<'_>,
+  ) -> fmt::Result {
+    match self {
+      CliError::UnknownFlag {
+        flag,
+        command,
+        suggestion,
+      } => {
+        write!(f, "Unknown flag '{}'", flag)?;
+        if let Some(suggestion) = suggestion {
+          write!(f, ", did you mean '{}'?", suggestion)?;
+        } else {
+          write!(f, ".")?;
+        }
+        write!(f, " Run '{} --help' to see the flags.", command)
+      }
+      CliError::MissingValue { flag, value_name } => {
+        write!(f, "Expected <{}> after '{}'.", value_name, flag)
+      }
+      CliError::UnexpectedValue { flag } => {
+        write!(f, "'{}' doesn't take a value.", flag)
+      }
+    }
+  }
+}
+
+impl Error for CliError {}
+
+const HELP_FLAG: FlagSpec = FlagSpec {
+  long: "--help",
+  short: Some("-h"),
+  value_name: None,
+  help: "Print help",
+};
+
+const VERSION_FLAG: FlagSpec = FlagSpec {
+  long: "--version",
+  short: Some("-V"),
+  value_name: None,
+  help: "Print version",
+};
+
+/// Figures out the subcommand, handles `--help` & `--version`, & checks the flags of the
+/// subcommand. `args[0]` is the program name.
+///
+/// Flags are only recognized before the positional args, so that the positional args can
+/// look like flags (eg: a search string like `-foo` or `--help`). The positional args start
+/// at the first arg that isn't a known flag (or a flag's value), except that an unknown arg
+/// starting w/ `--` is an error (it is most likely a typo). `--` can be used to end the
+/// flags explicitly, eg: `program -- --foo`.
+///
+/// The returned args are always in the form: program name, flags, `--`, positional args.
+/// Use [split_off_positional_args] to parse the flags, & [remove_separator] before the
+/// positional args are parsed.
+///
+/// When the first arg is the name of a subcommand, it is taken as the subcommand. To pass
+/// that name as a positional arg of the default subcommand instead, name the default
+/// subcommand explicitly (eg: `program search grapheme`), or pass `--` first (eg: `program
+/// -- grapheme`).
+pub fn parse(
+  spec: &'static CommandSpec,
+  version: &str,
+  mut args: Vec<String>,
+) -> Result<CliAction, CliError> {
+  let explicit_subcommand = args
+    .get(1)
+    .and_then(|arg| find_subcommand(spec, arg));
+  let subcommand = match explicit_subcommand {
+    Some(subcommand) => {
+      args.remove(1);
+      subcommand
+    }
+    None => match spec.subcommands.first() {
+      Some(subcommand) => subcommand,
+      None => spec,
+    },
+  };
+
+  let positional_args_index = scan_flags(spec, subcommand, &args)?;
+
+  let is_flag = |flag: &FlagSpec| {
+    args[..positional_args_index]
+      .iter()
+      .skip(1)
+      .any(|arg| arg == flag.long || Some(arg.as_str()) == flag.short)
+  };
+  if is_flag(&HELP_FLAG) {
+    return Ok(CliAction::PrintHelp(match explicit_subcommand {
+      Some(subcommand) => subcommand_help(spec, subcommand),
+      None => help(spec),
+    }));
+  }
+  if is_flag(&VERSION_FLAG) {
+    return Ok(CliAction::PrintVersion(format!("{} {}\n", spec.name, version)));
+  }
+
+  if args.get(positional_args_index).map(String::as_str) != Some(SEPARATOR) {
+    args.insert(positional_args_index, SEPARATOR.to_string());
+  }
+  Ok(CliAction::Run { subcommand, args })
+}
+
+/// Checks that every flag in `args` (skipping the program name) is one of the flags of the
+/// `subcommand`, & that flags which take a value have one. This is useful to re-check args
+/// that were changed after [parse], eg: by expanding aliases. Every arg before the `--`
+/// (that [parse] adds) must be a flag.
+pub fn check_flags(
+  spec: &CommandSpec,
+  subcommand: &CommandSpec,
+  args: &[String],
+) -> Result<(), CliError> {
+  let positional_args_index = scan_flags(spec, subcommand, args)?;
+  let separator_index = args.iter().position(|arg| arg == SEPARATOR);
+  match separator_index {
+    Some(separator_index) if positional_args_index < separator_index => {
+      let flag = &args[positional_args_index];
+      Err(CliError::UnknownFlag {
+        flag: flag.to_string(),
+        command: command_name(spec, subcommand),
+        suggestion: suggest_flag(subcommand, flag),
+      })
+    }
+    _ => Ok(()),
+  }
+}
+
+/// Splits off (& returns) the positional args, starting from the `--` that [parse] puts in
+/// front of them, so that only the flags are left in `args`. Once the flags have been
+/// taken out of `args`, the positional args can be put back using `args.extend()`. If there
+/// is no `--`, then all the args are left in `args`.
+pub fn split_off_positional_args(args: &mut Vec<String>) -> Vec<String> {
+  match args.iter().position(|arg| arg == SEPARATOR) {
+    Some(index) => args.split_off(index),
+    None => vec![],
+  }
+}
+
+/// Removes the `--` that [parse] puts in front of the positional args, once all the flags
+/// have been taken out of `args`.
+pub fn remove_separator(args: &mut Vec<String>) {
+  if let Some(index) = args.iter().position(|arg| arg == SEPARATOR) {
+    args.remove(index);
+  }
+}
+
+/// Removes a flag that doesn't take a value (eg: `--watch`) from the flags in `args`, &
+/// returns whether it was there.
+pub fn extract_switch(
+  args: &mut Vec<String>,
+  flag: &str,
+) -> bool {
+  let positional_args = split_off_positional_args(args);
+  let arg_count = args.len();
+  args.retain(|arg| arg != flag);
+  let is_present = args.len() != arg_count;
+  args.extend(positional_args);
+  is_present
+}
+
+const SEPARATOR: &str = "--";
+
+/// Returns the index of the first positional arg (or of the `--` in front of them), after
+/// checking the flags before it.
+fn scan_flags(
+  spec: &CommandSpec,
+  subcommand: &CommandSpec,
+  args: &[String],
+) -> Result<usize, CliError> {
+  let mut index = 1;
+  while let Some(arg) = args.get(index) {
+    if arg == SEPARATOR || !arg.starts_with('-') || arg == "-" {
+      return Ok(index);
+    }
+
+    let (flag, inline_value) = match arg.split_once('=') {
+      Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+      _ => (arg.as_str(), None),
+    };
+
+    let flag_spec = match subcommand
+      .flags
+      .iter()
+      .chain([HELP_FLAG, VERSION_FLAG].iter())
+      .find(|it| it.long == flag || it.short == Some(flag))
+    {
+      Some(flag_spec) => flag_spec,
+      None if flag.starts_with("--") => {
+        return Err(CliError::UnknownFlag {
+          flag: flag.to_string(),
+          command: command_name(spec, subcommand),
+          suggestion: suggest_flag(subcommand, flag),
+        })
+      }
+      // Eg: the search string `-foo`.
+      None => return Ok(index),
+    };
+
+    index += 1;
+    match (flag_spec.value_name, inline_value) {
+      (Some(value_name), None) => {
+        if index >= args.len() {
+          return Err(CliError::MissingValue {
+            flag: flag.to_string(),
+            value_name,
+          });
+        }
+        index += 1;
+      }
+      (None, Some(_)) => {
+        return Err(CliError::UnexpectedValue {
+          flag: flag.to_string(),
+        })
+      }
+      _ => {}
+    }
+  }
+  Ok(index)
+}
+
+fn find_subcommand(
+  spec: &'static CommandSpec,
+  name: &str,
+) -> Option<&'static CommandSpec> {
+  spec.subcommands.iter().find(|it| it.name == name)
+}
+
+fn command_name(
+  spec: &CommandSpec,
+  subcommand: &CommandSpec,
+) -> String {
+  match spec.name == subcommand.name {
+    true => spec.name.to_string(),
+    false => format!("{} {}", spec.name, subcommand.name),
+  }
+}
+
+/// Returns the long flag that is closest to `flag`, if it is a likely typo (eg: `--colour`
+/// -> `--color`).
+fn suggest_flag(
+  subcommand: &CommandSpec,
+  flag: &str,
+) -> Option<&'static str> {
+  const MAX_DISTANCE: usize = 2;
+  subcommand
+    .flags
+    .iter()
+    .map(|it| (edit_distance(it.long, flag), it.long))
+    .filter(|(distance, _)| *distance <= MAX_DISTANCE)
+    .min_by_key(|(distance, _)| *distance)
+    .map(|(_, long)| long)
+}
+
+/// Levenshtein distance, counted in chars.
+fn edit_distance(
+  lhs: &str,
+  rhs: &str,
+) -> usize {
+  let rhs: Vec<char> = rhs.chars().collect();
+  let mut previous_row: Vec<usize> = (0..=rhs.len()).collect();
+  for (i, lhs_char) in lhs.chars().enumerate() {
+    let mut current_row = vec![i + 1];
+    for (j, rhs_char) in rhs.iter().enumerate() {
+      let substitution_cost = previous_row[j] + usize::from(lhs_char != *rhs_char);
+      let deletion_cost = previous_row[j + 1] + 1;
+      let insertion_cost = current_row[j] + 1;
+      current_row.push(substitution_cost.min(deletion_cost).min(insertion_cost));
+    }
+    previous_row = current_row;
+  }
+  previous_row[rhs.len()]
+}
+
+/// Help for the top level command, which lists the subcommands & the flags of the default
+/// subcommand.
+pub fn help(spec: &CommandSpec) -> String {
+  let mut help = format!("{}\n\nUsage:", spec.about);
+  match spec.subcommands.first() {
+    None => help.push_str(&format!(" {} [options] {}\n", spec.name, spec.usage)),
+    Some(default_subcommand) => {
+      for (index, subcommand) in spec.subcommands.iter().enumerate() {
+        let name = match index {
+          0 => format!("[{}]", subcommand.name),
+          _ => subcommand.name.to_string(),
+        };
+        help.push_str(&format!(
+          " {} {} [options] {}\n      ",
+          spec.name, name, subcommand.usage
+        ));
+      }
+      help.truncate(help.trim_end().len());
+      help.push_str("\n\nCommands:\n");
+      help.push_str(&format_rows(spec.subcommands.iter().map(|it| {
+        (it.name.to_string(), it.about.to_string())
+      })));
+      help.push_str(&format!("\nOptions (for '{}'):\n", default_subcommand.name));
+      help.push_str(&format_flags(default_subcommand.flags));
+    }
+  }
+  help
+}
+
+pub fn subcommand_help(
+  spec: &CommandSpec,
+  subcommand: &CommandSpec,
+) -> String {
+  format!(
+    "{}\n\nUsage: {} [options] {}\n\nOptions:\n{}",
+    subcommand.about,
+    command_name(spec, subcommand),
+    subcommand.usage,
+    format_flags(subcommand.flags)
+  )
+}
+
+fn format_flags(flags: &[FlagSpec]) -> String {
+  format_rows(
+    flags
+      .iter()
+      .chain([HELP_FLAG, VERSION_FLAG].iter())
+      .map(|flag| {
+        let mut name = match flag.short {
+          Some(short) => format!("{}, {}", short, flag.long),
+          None => format!("    {}", flag.long),
+        };
+        if let Some(value_name) = flag.value_name {
+          name.push_str(&format!(" <{}>", value_name));
+        }
+        (name, flag.help.to_string())
+      }),
+  )
+}
+
+/// Formats 2 columns, w/ the 2nd column aligned.
+fn format_rows(rows: impl Iterator<Item = (String, String)>) -> String {
+  let rows: Vec<(String, String)> = rows.collect();
+  let width = rows
+    .iter()
+    .map(|(name, _)| name.chars().count())
+    .max()
+    .unwrap_or(0);
+  rows
+    .iter()
+    .map(|(name, help)| format!("  {:width$}  {}\n", name, help, width = width))
+    .collect()
+}
+
+#[cfg(test)]
+const TEST_SPEC: CommandSpec = CommandSpec {
+  name: "program",
+  about: "Test program.",
+  usage: "",
+  flags: &[],
+  subcommands: &[
+    CommandSpec {
+      name: "search",
+      about: "Search for things.",
+      usage: "<search-string>",
+      flags: &[
+        FlagSpec {
+          long: "--color",
+          short: None,
+          value_name: Some("when"),
+          help: "When to colorize",
+        },
+        FlagSpec {
+          long: "--context",
+          short: Some("-C"),
+          value_name: Some("n"),
+          help: "Print <n> lines around each match",
+        },
+        FlagSpec {
+          long: "--nfc",
+          short: None,
+          value_name: None,
+          help: "Normalize to NFC",
+        },
+      ],
+      subcommands: &[],
+    },
+    CommandSpec {
+      name: "count",
+      about: "Count things.",
+      usage: "<text>",
+      flags: &[],
+      subcommands: &[],
+    },
+  ],
+};
+
+#[cfg(test)]
+fn to_args(args: &[&str]) -> Vec<String> {
+  args
+    .iter()
+    .map(|it| it.to_string())
+    .collect()
+}
+
+#[test]
+fn test_parse_default_and_explicit_subcommands() {
+  match parse(&TEST_SPEC, "1.0", to_args(&["program", "-C", "2", "--nfc", "foo"])) {
+    Ok(CliAction::Run { subcommand, args }) => {
+      assert_eq!(subcommand.name, "search");
+      assert_eq!(args, to_args(&["program", "-C", "2", "--nfc", "--", "foo"]));
+    }
+    it => panic!("{:?}", it),
+  }
+
+  match parse(&TEST_SPEC, "1.0", to_args(&["program", "count", "foo"])) {
+    Ok(CliAction::Run { subcommand, args }) => {
+      assert_eq!(subcommand.name, "count");
+      assert_eq!(args, to_args(&["program", "--", "foo"]));
+    }
+    it => panic!("{:?}", it),
+  }
+}
+
+#[test]
+fn test_parse_help_and_version() {
+  assert_eq!(
+    parse(&TEST_SPEC, "1.0", to_args(&["program", "--version", "foo"])),
+    Ok(CliAction::PrintVersion("program 1.0\n".to_string()))
+  );
+
+  match parse(&TEST_SPEC, "1.0", to_args(&["program", "-h"])) {
+    Ok(CliAction::PrintHelp(help)) => {
+      assert!(help.starts_with(
+        "Test program.\n\nUsage: program [search] [options] <search-string>"
+      ));
+      assert!(help.contains("\n  search  Search for things.\n"));
+      assert!(help.contains("\n  -C, --context <n>   Print <n> lines around each match\n"));
+      assert!(help.contains("\n      --color <when>  When to colorize\n"));
+    }
+    it => panic!("{:?}", it),
+  }
+
+  match parse(&TEST_SPEC, "1.0", to_args(&["program", "count", "--help"])) {
+    Ok(CliAction::PrintHelp(help)) => {
+      assert!(help.starts_with("Count things.\n\nUsage: program count [options] <text>"));
+    }
+    it => panic!("{:?}", it),
+  }
+}
+
+#[test]
+fn test_parse_errors() {
+  assert_eq!(
+    parse(&TEST_SPEC, "1.0", to_args(&["program", "--colour=never", "foo"])),
+    Err(CliError::UnknownFlag {
+      flag: "--colour".to_string(),
+      command: "program search".to_string(),
+      suggestion: Some("--color"),
+    })
+  );
+  assert_eq!(
+    parse(&TEST_SPEC, "1.0", to_args(&["program", "count", "--nfc"])),
+    Err(CliError::UnknownFlag {
+      flag: "--nfc".to_string(),
+      command: "program count".to_string(),
+      suggestion: None,
+    })
+  );
+  assert_eq!(
+    parse(&TEST_SPEC, "1.0", to_args(&["program", "-C"])),
+    Err(CliError::MissingValue {
+      flag: "-C".to_string(),
+      value_name: "n",
+    })
+  );
+  assert_eq!(
+    parse(&TEST_SPEC, "1.0", to_args(&["program", "--nfc=yes", "foo"])),
+    Err(CliError::UnexpectedValue {
+      flag: "--nfc".to_string(),
+    })
+  );
+}
+
+#[cfg(test)]
+fn assert_run(
+  args: &[&str],
+  expected_subcommand: &str,
+  expected_args: &[&str],
+) {
+  match parse(&TEST_SPEC, "1.0", to_args(args)) {
+    Ok(CliAction::Run { subcommand, args }) => {
+      assert_eq!(subcommand.name, expected_subcommand);
+      assert_eq!(args, to_args(expected_args));
+    }
+    it => panic!("{:?}", it),
+  }
+}
+
+#[test]
+fn test_parse_positional_args_that_look_like_flags() {
+  // Unknown short flags, & everything after the first positional arg, are positional.
+  assert_run(&["program", "-foo", "file"], "search", &["program", "--", "-foo", "file"]);
+  assert_run(
+    &["program", "--nfc", "foo", "--help", "-C"],
+    "search",
+    &["program", "--nfc", "--", "foo", "--help", "-C"],
+  );
+
+  // `--` ends the flags.
+  assert_run(
+    &["program", "-C", "1", "--", "--foo", "file"],
+    "search",
+    &["program", "-C", "1", "--", "--foo", "file"],
+  );
+
+  // The name of a subcommand, as the search string.
+  assert_run(&["program", "--", "count"], "search", &["program", "--", "count"]);
+  assert_run(&["program", "search", "count"], "search", &["program", "--", "count"]);
+}
+
+#[test]
+fn test_check_flags_and_split_off_positional_args() {
+  let subcommand = &TEST_SPEC.subcommands[0];
+  let mut args = to_args(&["program", "--nfc", "-C", "2", "--", "--nfc", "file"]);
+  assert_eq!(check_flags(&TEST_SPEC, subcommand, &args), Ok(()));
+  assert!(extract_switch(&mut args, "--nfc"));
+  assert!(!extract_switch(&mut args, "--nfc"));
+  assert_eq!(args, to_args(&["program", "-C", "2", "--", "--nfc", "file"]));
+
+  let positional_args = split_off_positional_args(&mut args);
+  assert_eq!(args, to_args(&["program", "-C", "2"]));
+  args.extend(positional_args);
+  remove_separator(&mut args);
+  assert_eq!(args, to_args(&["program", "-C", "2", "--nfc", "file"]));
+
+  // Only flags can come before the `--`.
+  assert_eq!(
+    check_flags(
+      &TEST_SPEC,
+      subcommand,
+      &to_args(&["program", "-x", "--", "foo"])
+    ),
+    Err(CliError::UnknownFlag {
+      flag: "-x".to_string(),
+      command: "program search".to_string(),
+      suggestion: None,
+    })
+  );
+}
@@ -0,0 +1,334 @@
+use std::{collections::HashMap, env, error::Error, fs, path::PathBuf};
+
+use serde::Deserialize;
+
+use crate::{cli_args::split_off_positional_args, matcher::Normalization, output::ColorMode};
+
+const CONFIG_FILE_PATH: &str = "rust-grep/config.toml";
+
+/// Defaults & aliases that are loaded from `~/.config/rust-grep/config.toml` (or
+/// `$XDG_CONFIG_HOME/rust-grep/config.toml`). All the keys are optional, eg:
+///
+/// ```toml
+/// case_sensitive = true
+/// color = "never"
+/// context = 2
+/// grapheme_mode = true
+/// normalization = "nfc"
+/// ignore = ["*.min.js", "vendor"]
+///
+/// [aliases]
+/// todo = "TODO"
+/// fixme = ["FIXME", "-A", "3"]
+/// ```
+///
+/// Flags passed on the command line always win over the config:
+/// - `-A`, `-B` or `-C` on the command line replace all the context values in the config.
+/// - `--nfc` or `--nfd` on the command line replace the `normalization` in the config.
+/// - `--color` on the command line replaces the `color` in the config.
+/// - `case_sensitive` & `grapheme_mode` can only be turned on by the config (there are no
+///   flags to turn them off).
+/// - The `ignore` globs are added to the ones passed w/ `--ignore` on the command line.
+///
+/// An alias is used by passing `@<name>` as the search string (it isn't expanded anywhere
+/// else). It expands into the search string, & any flags it has are added before the other
+/// flags (so the ones on the command line win), eg: `@fixme file` -> `-A 3 -- FIXME file`. It is an error to use an alias that
+/// isn't in the config. To search for a string that starts w/ `@`, escape it w/ `@@`, eg:
+/// `@@todo` searches for `@todo`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+  pub case_sensitive: bool,
+  pub color: Option<ColorMode>,
+  pub context: Option<usize>,
+  pub before_context: Option<usize>,
+  pub after_context: Option<usize>,
+  pub grapheme_mode: bool,
+  pub normalization: Option<Normalization>,
+  pub ignore: Vec<String>,
+  pub aliases: HashMap<String, Alias>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Alias {
+  Search(String),
+  SearchWithFlags(Vec<String>),
+}
+
+impl Config {
+  /// Returns the default config if the config file doesn't exist.
+  pub fn load() -> Result<Config, Box<dyn Error>> {
+    match get_config_file_path() {
+      Some(path) if path.exists() => {
+        let content = fs::read_to_string(&path)?;
+        Config::parse(&content).map_err(|error| {
+          format!("Invalid config file {}: {}", path.display(), error).into()
+        })
+      }
+      _ => Ok(Config::default()),
+    }
+  }
+
+  pub fn parse(content: &str) -> Result<Config, toml::de::Error> { toml::from_str(content) }
+
+  /// Expands the alias in `args` (the canonical args returned by [crate::cli_args::parse])
+  /// & adds the flags for the config values that aren't overridden by `args`. The result
+  /// can be parsed by the options builders.
+  pub fn apply(
+    &self,
+    mut args: Vec<String>,
+  ) -> Result<Vec<String>, String> {
+    let mut positional_args = split_off_positional_args(&mut args);
+
+    // Expand the alias, which can only be the search string (the arg after the `--`). Its
+    // flags go before the other flags, since the last flag wins & the CLI flags have to win
+    // over the alias.
+    if let Some(search) = positional_args.get_mut(1) {
+      if let Some(name) = search.strip_prefix('@') {
+        *search = match name.strip_prefix('@') {
+          Some(_) => name.to_string(), // `@@` escapes the `@`.
+          None => match self.aliases.get(name) {
+            Some(Alias::Search(alias_search)) => alias_search.clone(),
+            Some(Alias::SearchWithFlags(alias_args)) if !alias_args.is_empty() => {
+              // `--nfc` & `--nfd` can't both be used, so the CLI's one replaces the
+              // alias's one.
+              let has_normalization = args.iter().any(|arg| is_normalization_flag(arg));
+              let alias_flags = alias_args[1..]
+                .iter()
+                .filter(|arg| !(has_normalization && is_normalization_flag(arg)))
+                .cloned();
+              let flags_start = args.len().min(1); // After the program name.
+              args.splice(flags_start..flags_start, alias_flags);
+              alias_args[0].clone()
+            }
+            _ => return Err(format!("Unknown alias '{}'.", search)),
+          },
+        };
+      }
+    }
+
+    let has_arg = |names: &[&str]| {
+      args.iter().any(|arg| {
+        names
+          .iter()
+          .any(|name| arg == name || arg.starts_with(&format!("{}=", name)))
+      })
+    };
+
+    let mut config_args: Vec<String> = Vec::new();
+
+    if !has_arg(&[
+      "-A",
+      "-B",
+      "-C",
+      "--after-context",
+      "--before-context",
+      "--context",
+    ]) {
+      for (flag, value) in [
+        ("--context", self.context),
+        ("--before-context", self.before_context),
+        ("--after-context", self.after_context),
+      ] {
+        if let Some(value) = value {
+          config_args.push(format!("{}={}", flag, value));
+        }
+      }
+    }
+
+    if self.case_sensitive && !has_arg(&["--case-sensitive"]) {
+      config_args.push("--case-sensitive".to_string());
+    }
+
+    if self.grapheme_mode && !has_arg(&["--grapheme-mode"]) {
+      config_args.push("--grapheme-mode".to_string());
+    }
+
+    if !has_arg(&["--nfc", "--nfd"]) {
+      match self.normalization {
+        Some(Normalization::Nfc) => config_args.push("--nfc".to_string()),
+        Some(Normalization::Nfd) => config_args.push("--nfd".to_string()),
+        None => {}
+      }
+    }
+
+    for pattern in &self.ignore {
+      config_args.push(format!("--ignore={}", pattern));
+    }
+
+    args.extend(config_args);
+    args.extend(positional_args);
+    Ok(args)
+  }
+}
+
+fn is_normalization_flag(arg: &str) -> bool { arg == "--nfc" || arg == "--nfd" }
+
+fn get_config_file_path() -> Option<PathBuf> {
+  let config_dir = match env::var_os("XDG_CONFIG_HOME") {
+    Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+    _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+  };
+  Some(config_dir.join(CONFIG_FILE_PATH))
+}
+
+#[test]
+fn test_parse_config() {
+  let config = Config::parse(
+    r#"
+      case_sensitive = true
+      color = "always"
+      context = 2
+      normalization = "nfd"
+
+      [aliases]
+      todo = "TODO"
+      fixme = ["FIXME", "-A", "3"]
+    "#,
+  )
+  .unwrap();
+  assert!(config.case_sensitive);
+  assert_eq!(config.color, Some(ColorMode::Always));
+  assert_eq!(config.context, Some(2));
+  assert_eq!(config.normalization, Some(Normalization::Nfd));
+  assert!(!config.grapheme_mode);
+  assert!(config.ignore.is_empty());
+  assert_eq!(
+    config.aliases.get("fixme"),
+    Some(&Alias::SearchWithFlags(vec![
+      "FIXME".to_string(),
+      "-A".to_string(),
+      "3".to_string()
+    ]))
+  );
+
+  assert!(Config::parse("colour = true").is_err());
+}
+
+#[cfg(test)]
+fn to_args(args: &[&str]) -> Vec<String> {
+  args
+    .iter()
+    .map(|it| it.to_string())
+    .collect()
+}
+
+#[test]
+fn test_apply_config_defaults() {
+  let config = Config::parse(
+    r#"
+      case_sensitive = true
+      context = 2
+      grapheme_mode = true
+      normalization = "nfc"
+      ignore = ["*.log", "vendor"]
+    "#,
+  )
+  .unwrap();
+  assert_eq!(
+    config
+      .apply(to_args(&["program", "--ignore=tmp", "--", "search", "file"]))
+      .unwrap(),
+    to_args(&[
+      "program",
+      "--ignore=tmp",
+      "--context=2",
+      "--case-sensitive",
+      "--grapheme-mode",
+      "--nfc",
+      "--ignore=*.log",
+      "--ignore=vendor",
+      "--",
+      "search",
+      "file",
+    ])
+  );
+}
+
+#[test]
+fn test_apply_config_cli_flags_win() {
+  let config =
+    Config::parse("context = 2\nafter_context = 5\nnormalization = \"nfc\"").unwrap();
+  assert_eq!(
+    config
+      .apply(to_args(&["program", "-C", "0", "--nfd", "--", "search"]))
+      .unwrap(),
+    to_args(&["program", "-C", "0", "--nfd", "--", "search"])
+  );
+  // Positional args that look like flags don't override the config.
+  assert_eq!(
+    config
+      .apply(to_args(&["program", "--", "--nfd", "-C"]))
+      .unwrap(),
+    to_args(&["program", "--context=2", "--after-context=5", "--nfc", "--", "--nfd", "-C"])
+  );
+}
+
+#[test]
+fn test_apply_config_aliases() {
+  let config = Config::parse(
+    r#"
+      [aliases]
+      todo = "TODO"
+      fixme = ["FIXME", "-A", "3"]
+    "#,
+  )
+  .unwrap();
+  assert_eq!(
+    config
+      .apply(to_args(&["program", "--", "@todo", "file"]))
+      .unwrap(),
+    to_args(&["program", "--", "TODO", "file"])
+  );
+  assert_eq!(
+    config
+      .apply(to_args(&["program", "--nfc", "--", "@fixme", "file"]))
+      .unwrap(),
+    to_args(&["program", "-A", "3", "--nfc", "--", "FIXME", "file"])
+  );
+  // Only the search string is expanded.
+  assert_eq!(
+    config
+      .apply(to_args(&["program", "--", "TODO", "@todo"]))
+      .unwrap(),
+    to_args(&["program", "--", "TODO", "@todo"])
+  );
+  // `@@` escapes the `@`.
+  assert_eq!(
+    config
+      .apply(to_args(&["program", "--", "@@todo", "file"]))
+      .unwrap(),
+    to_args(&["program", "--", "@todo", "file"])
+  );
+  assert!(config
+    .apply(to_args(&["program", "--", "@nope"]))
+    .is_err());
+}
+
+#[test]
+fn test_apply_config_cli_flags_win_over_alias_flags() {
+  use crate::{context::ContextOptions, matcher::MatchOptions};
+
+  let config = Config::parse(
+    r#"
+      [aliases]
+      fixme = ["FIXME", "-A", "3", "--nfd"]
+    "#,
+  )
+  .unwrap();
+  let mut args = config
+    .apply(to_args(&["program", "-A", "1", "--nfc", "--", "@fixme", "file"]))
+    .unwrap();
+  assert_eq!(
+    args,
+    to_args(&["program", "-A", "3", "-A", "1", "--nfc", "--", "FIXME", "file"])
+  );
+  assert_eq!(ContextOptions::extract_from(&mut args).unwrap().after, 1);
+  assert_eq!(
+    MatchOptions::extract_from(&mut args)
+      .unwrap()
+      .normalization,
+    Some(Normalization::Nfc)
+  );
+}
@@ -0,0 +1,286 @@
+use std::collections::VecDeque;
+
+use crate::{cli_args::split_off_positional_args, matcher::Matcher};
+
+/// How many lines of context to print before & after each matching line. This mirrors the
+/// `-A`, `-B` & `-C` flags of `grep`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ContextOptions {
+  pub before: usize,
+  pub after: usize,
+}
+
+impl ContextOptions {
+  pub fn is_enabled(&self) -> bool { self.before > 0 || self.after > 0 }
+
+  /// Removes the context flags from `args` (before the positional args) & returns the
+  /// options they hold, so the rest of the args can be parsed positionally. These flags are
+  /// supported:
+  /// - `-A <n>`, `--after-context <n>`, `--after-context=<n>`
+  /// - `-B <n>`, `--before-context <n>`, `--before-context=<n>`
+  /// - `-C <n>`, `--context <n>`, `--context=<n>`
+  ///
+  /// Just like `grep`, `-A` & `-B` take precedence over `-C` no matter the order.
+  pub fn extract_from(args: &mut Vec<String>) -> Result<ContextOptions, String> {
+    let mut before: Option<usize> = None;
+    let mut after: Option<usize> = None;
+    let mut both: Option<usize> = None;
+
+    let positional_args = split_off_positional_args(args);
+    let mut remaining_args = Vec::new();
+    let mut args_iter = args.drain(..);
+    while let Some(arg) = args_iter.next() {
+      let (flag, inline_value) = match arg.split_once('=') {
+        Some((flag, value)) if flag.starts_with("--") => {
+          (flag.to_string(), Some(value.to_string()))
+        }
+        _ => (arg.clone(), None),
+      };
+
+      let target = match flag.as_str() {
+        "-A" | "--after-context" => &mut after,
+        "-B" | "--before-context" => &mut before,
+        "-C" | "--context" => &mut both,
+        _ => {
+          remaining_args.push(arg);
+          continue;
+        }
+      };
+
+      let value = match inline_value {
+        Some(value) => value,
+        None => args_iter
+          .next()
+          .ok_or_else(|| format!("Expected a number of lines after '{}'.", flag))?,
+      };
+      *target = Some(value.parse().map_err(|_| {
+        format!("Expected a number of lines after '{}', got '{}'.", flag, value)
+      })?);
+    }
+    drop(args_iter);
+    *args = remaining_args;
+    args.extend(positional_args);
+
+    Ok(ContextOptions {
+      before: before.or(both).unwrap_or(0),
+      after: after.or(both).unwrap_or(0),
+    })
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OutputLine {
+  Match(String),
+  Context(String),
+  /// Printed as `--` between groups of lines that aren't next to each other.
+  Separator,
+  /// Printed instead of the lines of a binary file that matches.
+  BinaryMatch,
+}
+
+/// Matching lines are printed w/ the search string highlighted, context lines are printed
+/// as is.
+pub fn print_output_line(
+  output_line: OutputLine,
+  matcher: &Matcher,
+) {
+  println!("{}", format_output_line(output_line, matcher, None));
+}
+
+const BINARY_MATCH_MESSAGE: &str = "(binary file matched)";
+
+/// When there is a `path`, lines are prefixed w/ it like `grep -r` does: `path:line` for
+/// matching lines & `path-line` for context lines.
+pub fn format_output_line(
+  output_line: OutputLine,
+  matcher: &Matcher,
+  path: Option<&str>,
+) -> String {
+  match (output_line, path) {
+    (OutputLine::Match(line), None) => matcher.highlight(&line),
+    (OutputLine::Match(line), Some(path)) => format!("{}:{}", path, matcher.highlight(&line)),
+    (OutputLine::Context(line), None) => line,
+    (OutputLine::Context(line), Some(path)) => format!("{}-{}", path, line),
+    (OutputLine::Separator, _) => "--".to_string(),
+    (OutputLine::BinaryMatch, None) => BINARY_MATCH_MESSAGE.to_string(),
+    (OutputLine::BinaryMatch, Some(path)) => format!("{}: {}", path, BINARY_MATCH_MESSAGE),
+  }
+}
+
+/// Decides which lines of an input to print, given whether each one matches. Lines are
+/// pushed one at a time so that `stdin` can be streamed. Groups whose contexts overlap or
+/// touch are merged, so no line is printed twice.
+///
+/// Make a new one for each input (eg: file), since line numbers (used to detect gaps
+/// between groups) are counted from the first line that is pushed.
+pub struct ContextFilter {
+  options: ContextOptions,
+  line_number: usize,
+  last_printed_line_number: Option<usize>,
+  after_remaining: usize,
+  before_buffer: VecDeque<(usize, String)>,
+}
+
+impl ContextFilter {
+  pub fn new(options: ContextOptions) -> Self {
+    Self {
+      options,
+      line_number: 0,
+      last_printed_line_number: None,
+      after_remaining: 0,
+      before_buffer: VecDeque::with_capacity(options.before),
+    }
+  }
+
+  /// Returns the lines to print (possibly none) now that `line` has been seen.
+  pub fn push(
+    &mut self,
+    line: String,
+    is_match: bool,
+  ) -> Vec<OutputLine> {
+    let line_number = self.line_number;
+    self.line_number += 1;
+
+    let mut output = Vec::new();
+
+    if is_match {
+      let first_line_number = match self.before_buffer.front() {
+        Some((it, _)) => *it,
+        None => line_number,
+      };
+      self.push_separator_if_gap(first_line_number, &mut output);
+      output.extend(
+        self
+          .before_buffer
+          .drain(..)
+          .map(|(_, line)| OutputLine::Context(line)),
+      );
+      output.push(OutputLine::Match(line));
+      self.last_printed_line_number = Some(line_number);
+      self.after_remaining = self.options.after;
+    } else if self.after_remaining > 0 {
+      self.after_remaining -= 1;
+      output.push(OutputLine::Context(line));
+      self.last_printed_line_number = Some(line_number);
+    } else if self.options.before > 0 {
+      if self.before_buffer.len() == self.options.before {
+        self.before_buffer.pop_front();
+      }
+      self.before_buffer.push_back((line_number, line));
+    }
+
+    output
+  }
+
+  fn push_separator_if_gap(
+    &self,
+    first_line_number: usize,
+    output: &mut Vec<OutputLine>,
+  ) {
+    if !self.options.is_enabled() {
+      return;
+    }
+    if let Some(last_printed_line_number) = self.last_printed_line_number {
+      if first_line_number > last_printed_line_number + 1 {
+        output.push(OutputLine::Separator);
+      }
+    }
+  }
+}
+
+/// Golden output tests. Lines in `input.txt` that contain `match` are matches. The output
+/// is rendered w/ a `:` prefix for matching lines & a `-` prefix for context lines (like
+/// `grep -n` does).
+#[cfg(test)]
+fn render(options: ContextOptions) -> String {
+  let mut filter = ContextFilter::new(options);
+  let mut rendered = String::new();
+  for line in include_str!("../tests/golden/context/input.txt").lines() {
+    let is_match = line.contains("match");
+    for output_line in filter.push(line.to_string(), is_match) {
+      match output_line {
+        OutputLine::Match(line) => rendered.push_str(&format!(":{}\n", line)),
+        OutputLine::Context(line) => rendered.push_str(&format!("-{}\n", line)),
+        OutputLine::Separator => rendered.push_str("--\n"),
+        OutputLine::BinaryMatch => unreachable!(),
+      }
+    }
+  }
+  rendered
+}
+
+#[test]
+fn test_no_context() {
+  assert_eq!(
+    render(ContextOptions::default()),
+    include_str!("../tests/golden/context/no_context.txt")
+  );
+}
+
+#[test]
+fn test_after_context() {
+  assert_eq!(
+    render(ContextOptions {
+      before: 0,
+      after: 1
+    }),
+    include_str!("../tests/golden/context/after_1.txt")
+  );
+}
+
+#[test]
+fn test_before_context() {
+  assert_eq!(
+    render(ContextOptions {
+      before: 2,
+      after: 0
+    }),
+    include_str!("../tests/golden/context/before_2.txt")
+  );
+}
+
+#[test]
+fn test_overlapping_context() {
+  assert_eq!(
+    render(ContextOptions {
+      before: 2,
+      after: 2
+    }),
+    include_str!("../tests/golden/context/context_2.txt")
+  );
+}
+
+#[test]
+fn test_extract_from_args() {
+  let mut args = vec![
+    "program",
+    "-C",
+    "3",
+    "search",
+    "-A",
+    "1",
+    "--before-context=2",
+    "file",
+  ]
+  .into_iter()
+  .map(String::from)
+  .collect::<Vec<String>>();
+  let options = ContextOptions::extract_from(&mut args).unwrap();
+  assert_eq!(
+    options,
+    ContextOptions {
+      before: 2,
+      after: 1
+    }
+  );
+  assert_eq!(args, vec!["program", "search", "file"]);
+}
+
+#[test]
+fn test_extract_from_args_errors() {
+  let mut args = vec!["program".to_string(), "-A".to_string()];
+  assert!(ContextOptions::extract_from(&mut args).is_err());
+
+  let mut args = vec!["program".to_string(), "--context=lots".to_string()];
+  assert!(ContextOptions::extract_from(&mut args).is_err());
+}
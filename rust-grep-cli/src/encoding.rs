@@ -0,0 +1,265 @@
+use std::{char::{decode_utf16, REPLACEMENT_CHARACTER},
+          str};
+
+use crate::cli_args::split_off_positional_args;
+
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+const UTF16LE_BOM: &[u8] = &[0xff, 0xfe];
+const UTF16BE_BOM: &[u8] = &[0xfe, 0xff];
+
+/// How many bytes (from the start of a file) are looked at to guess its encoding.
+const SNIFF_LEN: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+  Utf8,
+  Utf16Le,
+  Utf16Be,
+  Latin1,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Decoded {
+  Text(String),
+  Binary,
+}
+
+impl Encoding {
+  /// Removes the `--encoding=<name>` (or `--encoding <name>`) flags from `args` (before the
+  /// positional args) & returns the encoding that the last one holds, or `None` if none
+  /// were passed or it is `auto`. The name is one of: `auto`, `utf-8`, `utf-16le`,
+  /// `utf-16be`, `latin1`.
+  pub fn extract_from(args: &mut Vec<String>) -> Result<Option<Encoding>, String> {
+    let mut encoding: Option<Encoding> = None;
+
+    let positional_args = split_off_positional_args(args);
+    let mut remaining_args = Vec::new();
+    let mut args_iter = args.drain(..);
+    while let Some(arg) = args_iter.next() {
+      let value = match arg.strip_prefix("--encoding=") {
+        Some(value) => value.to_string(),
+        None if arg == "--encoding" => args_iter
+          .next()
+          .ok_or_else(|| "Expected an encoding after '--encoding'.".to_string())?,
+        None => {
+          remaining_args.push(arg);
+          continue;
+        }
+      };
+      encoding = match value.to_lowercase().as_str() {
+        "auto" => None,
+        "utf-8" | "utf8" => Some(Encoding::Utf8),
+        "utf-16le" | "utf16le" => Some(Encoding::Utf16Le),
+        "utf-16be" | "utf16be" => Some(Encoding::Utf16Be),
+        "latin1" | "latin-1" | "iso-8859-1" => Some(Encoding::Latin1),
+        _ => {
+          return Err(format!(
+            "Expected auto, utf-8, utf-16le, utf-16be or latin1 after '--encoding', got '{}'.",
+            value
+          ))
+        }
+      };
+    }
+    drop(args_iter);
+    *args = remaining_args;
+    args.extend(positional_args);
+
+    Ok(encoding)
+  }
+
+  /// Guesses the encoding of the bytes of a file, or returns `None` if they are binary.
+  /// 1. A BOM wins.
+  /// 2. UTF-16 w/out a BOM is spotted by the zero bytes in every other position (which is
+  ///    what mostly ASCII text looks like in UTF-16).
+  /// 3. Anything else that has a zero byte, or lots of control characters, is binary.
+  /// 4. Valid UTF-8 is UTF-8, and the rest is Latin-1 (which can decode any byte).
+  pub fn detect(bytes: &[u8]) -> Option<Encoding> {
+    if bytes.starts_with(UTF8_BOM) {
+      return Some(Encoding::Utf8);
+    }
+    if bytes.starts_with(UTF16LE_BOM) {
+      return Some(Encoding::Utf16Le);
+    }
+    if bytes.starts_with(UTF16BE_BOM) {
+      return Some(Encoding::Utf16Be);
+    }
+
+    let sample = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if let Some(encoding) = detect_utf16_without_bom(sample) {
+      return Some(encoding);
+    }
+    if is_binary(sample) {
+      return None;
+    }
+
+    match str::from_utf8(bytes) {
+      Ok(_) => Some(Encoding::Utf8),
+      Err(_) => Some(Encoding::Latin1),
+    }
+  }
+
+  /// Invalid sequences are replaced w/ `U+FFFD`. The BOM (if any) is dropped.
+  pub fn decode(
+    self,
+    bytes: &[u8],
+  ) -> String {
+    match self {
+      Encoding::Utf8 => {
+        String::from_utf8_lossy(bytes.strip_prefix(UTF8_BOM).unwrap_or(bytes)).into_owned()
+      }
+      Encoding::Utf16Le => decode_utf16_bytes(
+        bytes.strip_prefix(UTF16LE_BOM).unwrap_or(bytes),
+        u16::from_le_bytes,
+      ),
+      Encoding::Utf16Be => decode_utf16_bytes(
+        bytes.strip_prefix(UTF16BE_BOM).unwrap_or(bytes),
+        u16::from_be_bytes,
+      ),
+      Encoding::Latin1 => bytes.iter().map(|&byte| byte as char).collect(),
+    }
+  }
+}
+
+/// Decodes the bytes of a whole file, using `encoding` if it is given (in which case the
+/// bytes are never treated as binary), or the one that [Encoding::detect] guesses.
+pub fn decode(
+  bytes: &[u8],
+  encoding: Option<Encoding>,
+) -> Decoded {
+  match encoding.or_else(|| Encoding::detect(bytes)) {
+    Some(encoding) => Decoded::Text(encoding.decode(bytes)),
+    None => Decoded::Binary,
+  }
+}
+
+/// Decodes 1 line of a stream (eg: `stdin`), which can't be sniffed as a whole. W/out an
+/// `encoding`, lines that aren't valid UTF-8 are decoded as Latin-1.
+pub fn decode_line(
+  bytes: &[u8],
+  encoding: Option<Encoding>,
+) -> String {
+  match (encoding, str::from_utf8(bytes)) {
+    (Some(encoding), _) => encoding.decode(bytes),
+    (None, Ok(line)) => line.to_string(),
+    (None, Err(_)) => Encoding::Latin1.decode(bytes),
+  }
+}
+
+fn decode_utf16_bytes(
+  bytes: &[u8],
+  from_bytes: fn([u8; 2]) -> u16,
+) -> String {
+  decode_utf16(
+    bytes
+      .chunks_exact(2)
+      .map(|pair| from_bytes([pair[0], pair[1]])),
+  )
+  .map(|it| it.unwrap_or(REPLACEMENT_CHARACTER))
+  .collect()
+}
+
+/// Too short a sample can look like anything.
+const MIN_UTF16_SAMPLE_PAIRS: usize = 4;
+
+fn detect_utf16_without_bom(sample: &[u8]) -> Option<Encoding> {
+  let pair_count = sample.len() / 2;
+  if pair_count < MIN_UTF16_SAMPLE_PAIRS {
+    return None;
+  }
+  let count_zeros = |offset: usize| {
+    sample
+      .iter()
+      .skip(offset)
+      .step_by(2)
+      .filter(|&&byte| byte == 0)
+      .count()
+  };
+  let (even_zero_count, odd_zero_count) = (count_zeros(0), count_zeros(1));
+  let is_mostly = |count: usize| count * 10 >= pair_count * 4;
+  let is_rare = |count: usize| count * 20 <= pair_count;
+  if is_mostly(odd_zero_count) && is_rare(even_zero_count) {
+    Some(Encoding::Utf16Le)
+  } else if is_mostly(even_zero_count) && is_rare(odd_zero_count) {
+    Some(Encoding::Utf16Be)
+  } else {
+    None
+  }
+}
+
+/// Text has no zero bytes, & few control characters (other than whitespace & escapes).
+fn is_binary(sample: &[u8]) -> bool {
+  if sample.contains(&0) {
+    return true;
+  }
+  let control_count = sample
+    .iter()
+    .filter(|&&byte| byte < 0x20 && !b"\t\n\r\x0c\x1b".contains(&byte))
+    .count();
+  control_count * 10 > sample.len()
+}
+
+#[test]
+fn test_detect_encoding() {
+  assert_eq!(Encoding::detect(b"\xef\xbb\xbfhi"), Some(Encoding::Utf8));
+  assert_eq!(Encoding::detect(b"\xff\xfeh\x00i\x00"), Some(Encoding::Utf16Le));
+  assert_eq!(Encoding::detect(b"\xfe\xff\x00h\x00i"), Some(Encoding::Utf16Be));
+  assert_eq!(Encoding::detect(b"h\x00e\x00y\x00!\x00"), Some(Encoding::Utf16Le));
+  assert_eq!(Encoding::detect(b"\x00h\x00e\x00y\x00!"), Some(Encoding::Utf16Be));
+  assert_eq!(Encoding::detect("café 🙏🏽".as_bytes()), Some(Encoding::Utf8));
+  assert_eq!(Encoding::detect(b"caf\xe9"), Some(Encoding::Latin1));
+  assert_eq!(Encoding::detect(b"\x7fELF\x02\x01\x01\x00\x00"), None);
+  assert_eq!(Encoding::detect(b"\x01\x02\x03\x04abc"), None);
+  assert_eq!(Encoding::detect(b"a\x00hi"), None);
+}
+
+#[test]
+fn test_decode() {
+  assert_eq!(
+    decode(b"\xff\xfec\x00a\x00f\x00\xe9\x00", None),
+    Decoded::Text("café".to_string())
+  );
+  assert_eq!(
+    decode(b"caf\xe9\nno", None),
+    Decoded::Text("café\nno".to_string())
+  );
+  assert_eq!(decode(b"a\x00b", None), Decoded::Binary);
+  // A forced encoding is never binary.
+  assert_eq!(
+    decode(b"a\x00b", Some(Encoding::Latin1)),
+    Decoded::Text("a\u{0}b".to_string())
+  );
+  assert_eq!(decode_line(b"caf\xe9", None), "café");
+  assert_eq!(decode_line("café".as_bytes(), None), "café");
+}
+
+#[test]
+fn test_extract_encoding_from_args() {
+  let mut args = vec![
+    "program".to_string(),
+    "--encoding".to_string(),
+    "UTF-16LE".to_string(),
+    "search".to_string(),
+  ];
+  assert_eq!(
+    Encoding::extract_from(&mut args),
+    Ok(Some(Encoding::Utf16Le))
+  );
+  assert_eq!(args, vec!["program", "search"]);
+
+  let mut args = vec!["--encoding=auto".to_string()];
+  assert_eq!(Encoding::extract_from(&mut args), Ok(None));
+
+  // The last one wins, & none of them are left in the args.
+  let mut args = vec![
+    "program".to_string(),
+    "--encoding=latin1".to_string(),
+    "--encoding".to_string(),
+    "utf-8".to_string(),
+    "search".to_string(),
+  ];
+  assert_eq!(Encoding::extract_from(&mut args), Ok(Some(Encoding::Utf8)));
+  assert_eq!(args, vec!["program", "search"]);
+
+  let mut args = vec!["--encoding=ebcdic".to_string()];
+  assert!(Encoding::extract_from(&mut args).is_err());
+}
@@ -0,0 +1,57 @@
+use std::error::Error;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{grapheme_command_builder::GraphemeOptions,
+            matcher::normalize,
+            output::{primary, prompt}};
+
+/// Prints each grapheme cluster in the text, which is handy to see what
+/// `--grapheme-mode` will (& won't) match.
+pub fn grapheme(options: GraphemeOptions) -> Result<(), Box<dyn Error>> {
+  println!(
+    "{}: grapheme clusters in '{}'",
+    prompt("DEBUG"),
+    options.text
+  );
+  let text = normalize(&options.text, options.normalization);
+  for (index, (byte_offset, cluster)) in text.grapheme_indices(true).enumerate() {
+    println!(
+      "{}: {} {}",
+      index,
+      primary(cluster),
+      describe_cluster(byte_offset, cluster)
+    );
+  }
+  Ok(())
+}
+
+/// Eg: `(byte offset 7, 8 bytes, U+1F64F U+1F3FD)`.
+fn describe_cluster(
+  byte_offset: usize,
+  cluster: &str,
+) -> String {
+  let code_points = cluster
+    .chars()
+    .map(|it| format!("U+{:04X}", it as u32))
+    .collect::<Vec<String>>()
+    .join(" ");
+  format!(
+    "(byte offset {}, {} bytes, {})",
+    byte_offset,
+    cluster.len(),
+    code_points
+  )
+}
+
+#[test]
+fn test_describe_cluster() {
+  assert_eq!(
+    describe_cluster(7, "🙏🏽"),
+    "(byte offset 7, 8 bytes, U+1F64F U+1F3FD)"
+  );
+  assert_eq!(
+    describe_cluster(0, "e\u{301}"),
+    "(byte offset 0, 3 bytes, U+0065 U+0301)"
+  );
+}
@@ -0,0 +1,58 @@
+use crate::{cli_args::remove_separator,
+            matcher::{MatchOptions, Normalization}};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GraphemeOptions {
+  pub text: String,
+  pub normalization: Option<Normalization>,
+}
+
+const REQUIRED_ARGS_COUNT: usize = 2;
+
+pub struct GraphemeOptionsBuilder;
+
+impl GraphemeOptionsBuilder {
+  pub fn parse(mut args: Vec<String>) -> Result<GraphemeOptions, String> {
+    let matching = MatchOptions::extract_from(&mut args)?;
+    remove_separator(&mut args);
+
+    if args.len() != REQUIRED_ARGS_COUNT {
+      return Err(format!(
+        "Expected {} arguments, got {}.",
+        REQUIRED_ARGS_COUNT,
+        args.len()
+      ));
+    }
+
+    Ok(GraphemeOptions {
+      text: args.remove(1),
+      normalization: matching.normalization,
+    })
+  }
+}
+
+#[test]
+fn test_can_build_options_with_text_and_normalization() {
+  let options = GraphemeOptionsBuilder::parse(vec![
+    String::from("program"),
+    String::from("--nfd"),
+    String::from("🙏🏽"),
+  ]);
+  assert_eq!(
+    options,
+    Ok(GraphemeOptions {
+      text: String::from("🙏🏽"),
+      normalization: Some(Normalization::Nfd),
+    })
+  );
+}
+
+#[test]
+fn test_can_not_build_options_with_extra_args() {
+  let options = GraphemeOptionsBuilder::parse(vec![
+    String::from("program"),
+    String::from("one"),
+    String::from("two"),
+  ]);
+  assert!(options.is_err());
+}
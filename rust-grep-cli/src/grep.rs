@@ -1,13 +1,20 @@
-use std::{error::Error, fs};
+use std::{error::Error,
+          fs,
+          io::stdout,
+          path::Path};
 
-use r3bl_rs_utils::utils::{style_primary, style_prompt};
-
-use crate::grep_command_builder::GrepOptions;
+use crate::{context::{print_output_line, ContextFilter, ContextOptions, OutputLine},
+            encoding::{decode, Decoded},
+            grep_command_builder::GrepOptions,
+            matcher::Matcher,
+            output::prompt,
+            pipeline::search_dir,
+            watch::watch};
 
 pub fn grep(options: GrepOptions) -> Result<(), Box<dyn Error>> {
   println!(
     "{}: search for '{}' in '{}' w/ {}",
-    style_prompt("DEBUG"),
+    prompt("DEBUG"),
     options.search,
     options.file_path,
     match options.case_sensitive {
@@ -16,23 +23,105 @@ pub fn grep(options: GrepOptions) -> Result<(), Box<dyn Error>> {
     }
   );
 
-  let content = fs::read_to_string(options.file_path)?;
-  let filtered_content = content
-    .lines()
-    .filter(|line| {
-      if options.case_sensitive {
-        line.contains(&options.search)
-      } else {
-        line.to_lowercase().contains(&options.search.to_lowercase())
+  let matcher = Matcher::new(&options.search, options.case_sensitive, options.matching);
+
+  let path = Path::new(&options.file_path);
+  match options.watch {
+    true => watch(path, || search_path(path, &matcher, &options)),
+    false => search_path(path, &matcher, &options),
+  }
+}
+
+fn search_path(
+  path: &Path,
+  matcher: &Matcher,
+  options: &GrepOptions,
+) -> Result<(), Box<dyn Error>> {
+  if path.is_dir() {
+    let worker_stats_vec = search_dir(
+      path,
+      matcher,
+      options.context,
+      options.encoding,
+      &options.pipeline,
+      &mut stdout().lock(),
+    )?;
+    if options.pipeline.stats {
+      for worker_stats in worker_stats_vec {
+        eprintln!("{}: {}", prompt("STATS"), worker_stats);
       }
-    })
-    .map(|line| {
-      let from = &options.search;
-      let to = format!("{}", style_primary(&options.search));
-      line.replace(from, &to)
-    })
-    .collect::<Vec<String>>();
-  println!("{}", filtered_content.join("\n"));
+    }
+    return Ok(());
+  }
+
+  let bytes = fs::read(path)?;
+  let output_line_vec = match decode(&bytes, options.encoding) {
+    Decoded::Text(content) => search_content(&content, matcher, options.context),
+    Decoded::Binary => search_binary(&bytes, matcher),
+  };
+  output_line_vec
+    .into_iter()
+    .for_each(|output_line| print_output_line(output_line, matcher));
 
   Ok(())
 }
+
+/// Returns the lines to print for the `content` of a file.
+pub fn search_content(
+  content: &str,
+  matcher: &Matcher,
+  context: ContextOptions,
+) -> Vec<OutputLine> {
+  let mut context_filter = ContextFilter::new(context);
+  content
+    .lines()
+    .flat_map(|line| {
+      let is_match = matcher.is_match(line);
+      context_filter.push(line.to_string(), is_match)
+    })
+    .collect()
+}
+
+/// Binary content isn't printed, so this only returns a single [OutputLine::BinaryMatch] if
+/// any of its (lossily decoded) lines match.
+pub fn search_binary(
+  bytes: &[u8],
+  matcher: &Matcher,
+) -> Vec<OutputLine> {
+  let is_match = String::from_utf8_lossy(bytes)
+    .lines()
+    .any(|line| matcher.is_match(line));
+  match is_match {
+    true => vec![OutputLine::BinaryMatch],
+    false => vec![],
+  }
+}
+
+#[test]
+fn test_search_content_keeps_the_original_lines() {
+  use crate::matcher::{MatchOptions, Normalization};
+
+  let matcher = Matcher::new(
+    "caf\u{e9}",
+    true,
+    MatchOptions {
+      grapheme_mode: false,
+      normalization: Some(Normalization::Nfc),
+    },
+  );
+  let output_line_vec = search_content(
+    "cafe\u{301}\ntea\n",
+    &matcher,
+    ContextOptions {
+      before: 0,
+      after: 1,
+    },
+  );
+  assert_eq!(
+    output_line_vec,
+    vec![
+      OutputLine::Match("cafe\u{301}".to_string()),
+      OutputLine::Context("tea".to_string())
+    ]
+  );
+}
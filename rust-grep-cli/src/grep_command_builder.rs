@@ -1,8 +1,23 @@
+use crate::{cli_args::{extract_switch, remove_separator},
+            context::ContextOptions,
+            encoding::Encoding,
+            matcher::MatchOptions,
+            pipeline::PipelineOptions,
+            watch::extract_watch_flag};
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GrepOptions {
   pub search: String,
   pub file_path: String,
   pub case_sensitive: bool,
+  pub context: ContextOptions,
+  pub matching: MatchOptions,
+  /// `None` to detect the encoding of each file.
+  pub encoding: Option<Encoding>,
+  /// Only used when `file_path` is a directory.
+  pub pipeline: PipelineOptions,
+  /// Re-run the search each time the file (or a file in the directory) changes.
+  pub watch: bool,
 }
 
 const REQUIRED_ARGS_COUNT: usize = 3;
@@ -10,7 +25,15 @@ const REQUIRED_ARGS_COUNT: usize = 3;
 pub struct GrepOptionsBuilder;
 
 impl GrepOptionsBuilder {
-  pub fn parse(args: Vec<String>) -> Result<GrepOptions, String> {
+  pub fn parse(mut args: Vec<String>) -> Result<GrepOptions, String> {
+    let context = ContextOptions::extract_from(&mut args)?;
+    let matching = MatchOptions::extract_from(&mut args)?;
+    let encoding = Encoding::extract_from(&mut args)?;
+    let pipeline = PipelineOptions::extract_from(&mut args)?;
+    let watch = extract_watch_flag(&mut args);
+    let case_sensitive = extract_switch(&mut args, "--case-sensitive");
+    remove_separator(&mut args);
+
     if args.len() < REQUIRED_ARGS_COUNT {
       return Err(format!(
         "Expected at least {} arguments, got {}.",
@@ -31,7 +54,13 @@ impl GrepOptionsBuilder {
         Some(arg) => arg.clone(),
         None => String::new(),
       },
-      case_sensitive: args.next().is_some(), // If 3rd arg exists, then true.
+      // The flag, or (for backwards compatibility) any extra positional arg.
+      case_sensitive: case_sensitive || args.next().is_some(),
+      context,
+      matching,
+      encoding,
+      pipeline,
+      watch,
     };
 
     Ok(options)
@@ -56,6 +85,25 @@ fn test_can_build_options_with_3_args() {
   }
 }
 
+#[test]
+fn test_can_build_options_with_case_sensitive_flag() {
+  let options = GrepOptionsBuilder::parse(vec![
+    String::from("program"),
+    String::from("--case-sensitive"),
+    String::from("--"),
+    String::from("--case-sensitive"),
+    String::from("path-to-file"),
+  ]);
+  match options {
+    Ok(options) => {
+      assert_eq!(options.search, "--case-sensitive");
+      assert_eq!(options.file_path, "path-to-file");
+      assert!(options.case_sensitive);
+    }
+    Err(error) => panic!("{}", error),
+  }
+}
+
 #[test]
 fn test_can_build_options_with_2_args() {
   let options = GrepOptionsBuilder::parse(vec![
@@ -73,6 +121,33 @@ fn test_can_build_options_with_2_args() {
   }
 }
 
+#[test]
+fn test_can_build_options_with_context_flags() {
+  let options = GrepOptionsBuilder::parse(vec![
+    String::from("program"),
+    String::from("-C"),
+    String::from("2"),
+    String::from("search-string"),
+    String::from("path-to-file"),
+    String::from("--after-context=1"),
+  ]);
+  match options {
+    Ok(options) => {
+      assert_eq!(options.search, "search-string");
+      assert_eq!(options.file_path, "path-to-file");
+      assert!(!options.case_sensitive);
+      assert_eq!(
+        options.context,
+        ContextOptions {
+          before: 2,
+          after: 1
+        }
+      );
+    }
+    Err(error) => panic!("{}", error),
+  }
+}
+
 #[test]
 #[should_panic]
 fn test_can_not_build_options_with_1_args() {
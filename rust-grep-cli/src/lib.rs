@@ -0,0 +1,5 @@
+//! The parts of this crate that aren't specific to the `rust_book` binary, so that other
+//! binaries can reuse them.
+
+pub mod cli_args;
+pub mod output;
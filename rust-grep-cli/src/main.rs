@@ -1,32 +1,109 @@
 // Connect to Rust source files.
+mod config;
+mod context;
+mod encoding;
+mod grapheme;
+mod grapheme_command_builder;
 mod grep;
 mod grep_command_builder;
+mod matcher;
 mod piped_grep;
 mod piped_grep_command_builder;
+mod pipeline;
+mod watch;
 
 // Imports.
+use rust_grep_cli::{cli_args::{self, CliAction, CommandSpec, FlagSpec},
+                    output::{self, ColorMode}};
+use config::Config;
+use grapheme::grapheme;
+use grapheme_command_builder::GraphemeOptionsBuilder;
 use grep::grep;
 use grep_command_builder::GrepOptionsBuilder;
 use piped_grep::piped_grep;
 use piped_grep_command_builder::PipedGrepOptionsBuilder;
-use r3bl_rs_utils::utils::{is_stdin_piped, style_error, with};
+use r3bl_rs_utils::utils::{is_stdin_piped, with};
 use std::env::args;
 use std::error::Error;
 use std::process::exit;
 
-/// This program has 2 modes of operation.
+/// This program has 2 subcommands, `search` (the default, so its name can be left out) &
+/// `grapheme`. Run it w/ `--help` to see the flags of each one.
 ///
-/// # Mode 1 - Not using `stdin` (input is not piped into this program).
+/// # `search` has 2 modes of operation.
+///
+/// ## Mode 1 - Not using `stdin` (input is not piped into this program).
 ///
 /// <search-string> <path-to-file> <case-sensitive>
 ///       ↑               ↑              ↑          
 ///     arg 0           arg 0          arg 0   
 ///
-/// # Mode 2 - Using `stdin` (input is piped into this program).
+/// `--case-sensitive` does the same as passing the 3rd arg.
+///
+/// ## Flags
+///
+/// Flags must come before the positional args, so that the search string can look like a
+/// flag (eg: `rust_book -foo file`). `--` ends the flags explicitly (eg: `rust_book --
+/// --help file` searches for `--help`). The same goes for the name of a subcommand, eg: to
+/// search for `grapheme` use `rust_book search grapheme file` or `rust_book -- grapheme
+/// file`.
+///
+/// ## Context lines
+///
+/// In both modes, `-A <n>`, `-B <n>` & `-C <n>` (or `--after-context`, `--before-context` &
+/// `--context`) print lines around each match, like `grep` does.
+///
+/// ## Directories
+///
+/// If `<path-to-file>` is a directory, every file under it is searched (in parallel) & each
+/// line is prefixed w/ its file's path. The output is in path order, no matter how many
+/// threads are used. `--threads <n>` sets the number of worker threads (default: 1 per
+/// CPU), and `--stats` prints what each worker did to `stderr`. Version control, build output
+/// & dependency directories (`.git`, `.hg`, `.svn`, `target`, `node_modules`) are skipped,
+/// unless `--no-ignore` is passed. `--ignore <glob>` skips the files & directories whose
+/// name matches the glob (`*` & `?` are supported), eg: `--ignore '*.min.js'`.
+///
+/// ## Unicode
+///
+/// In both modes, `--grapheme-mode` only matches whole grapheme clusters (so "🙏" doesn't
+/// match "🙏🏽"), and `--nfc` or `--nfd` normalizes the search string & each line before
+/// matching (the lines are printed as they are).
+///
+/// ## Watch mode
+///
+/// `--watch` keeps the program running after the search, & re-runs it each time the file
+/// (or a file in the directory) changes. The paths that changed are printed before the
+/// results of each re-run. If a re-run fails (eg: the file was removed), the error is
+/// printed to `stderr` & the program keeps watching.
+///
+/// ## Encodings
+///
+/// Files are decoded as UTF-8, UTF-16 (LE or BE) or Latin-1, which is detected from the
+/// BOM or guessed from the content. Binary files only print `(binary file matched)` if they
+/// match. `--encoding=utf-8|utf-16le|utf-16be|latin1` skips the detection (`auto` is the
+/// default). `stdin` is read as UTF-8, w/ a fallback to Latin-1 for each invalid line.
+///
+/// ## Color
+///
+/// In both modes, `--color=auto|always|never` controls the colors. The default is `auto`,
+/// which only colorizes output to a terminal, when the `NO_COLOR` env var isn't set.
+///
+/// ## Config file
+///
+/// Defaults for the flags, globs to ignore & search aliases (eg: `@todo`, use `@@` to search
+/// for a string that starts w/ `@`) can be set in
+/// `~/.config/rust-grep/config.toml`, more info in [config::Config].
+///
+/// ## Mode 2 - Using `stdin` (input is piped into this program).
 ///
 /// Content is piped into the program via `stdin` and it only needs to take the search string and
 /// the case sensitive matching as arguments.
 ///
+/// # `grapheme`
+///
+/// Prints each grapheme cluster in the given text (w/ its byte offset & code points), eg:
+/// `rust_book grapheme "🙏🏽 hi"`. It takes the `--nfc`, `--nfd` & `--color` flags.
+///
 /// More info on `Box<dyn Error>` or `&'static dyn Error`:
 /// - `'static` is the lifetime of `Box<dyn Error>`.
 /// - <https://users.rust-lang.org/t/what-does-it-mean-to-return-dyn-error-static/37619/7>
@@ -36,16 +113,159 @@ fn main() {
   with(run(args), |it| match it {
     Ok(()) => exit(0),
     Err(err) => {
-      eprintln!("{}: {}", style_error("Problem encountered"), err);
+      eprintln!("{}: {}", output::error("Problem encountered"), err);
       exit(1);
     }
   });
 }
 
 fn run(args: Vec<String>) -> Result<(), Box<dyn Error>> {
-  match is_stdin_piped() {
-    true => piped_grep(PipedGrepOptionsBuilder::parse(args)?)?,
-    false => grep(GrepOptionsBuilder::parse(args)?)?,
+  let (subcommand, args) = match cli_args::parse(&CLI_SPEC, env!("CARGO_PKG_VERSION"), args)? {
+    CliAction::Run { subcommand, args } => (subcommand, args),
+    CliAction::PrintHelp(text) | CliAction::PrintVersion(text) => {
+      print!("{}", text);
+      return Ok(());
+    }
+  };
+
+  let config = Config::load()?;
+  let mut args = match subcommand.name {
+    SEARCH_SUBCOMMAND_NAME => {
+      // Aliases can expand into flags, so they have to be checked again.
+      let args = config.apply(args)?;
+      cli_args::check_flags(&CLI_SPEC, subcommand, &args)?;
+      args
+    }
+    _ => args,
+  };
+  ColorMode::extract_from(&mut args)?
+    .or(config.color)
+    .unwrap_or_default()
+    .set_global();
+
+  match subcommand.name {
+    GRAPHEME_SUBCOMMAND_NAME => grapheme(GraphemeOptionsBuilder::parse(args)?)?,
+    _ => match is_stdin_piped() {
+      true => piped_grep(PipedGrepOptionsBuilder::parse(args)?)?,
+      false => grep(GrepOptionsBuilder::parse(args)?)?,
+    },
   }
   Ok(())
 }
+
+const SEARCH_SUBCOMMAND_NAME: &str = "search";
+const GRAPHEME_SUBCOMMAND_NAME: &str = "grapheme";
+
+const COLOR_FLAG: FlagSpec = FlagSpec {
+  long: "--color",
+  short: None,
+  value_name: Some("auto|always|never"),
+  help: "When to colorize the output (default: auto)",
+};
+
+const NFC_FLAG: FlagSpec = FlagSpec {
+  long: "--nfc",
+  short: None,
+  value_name: None,
+  help: "Normalize the text to NFC first",
+};
+
+const NFD_FLAG: FlagSpec = FlagSpec {
+  long: "--nfd",
+  short: None,
+  value_name: None,
+  help: "Normalize the text to NFD first",
+};
+
+const CLI_SPEC: CommandSpec = CommandSpec {
+  name: "rust_book",
+  about: "A very basic implementation of grep.",
+  usage: "",
+  flags: &[],
+  subcommands: &[
+    CommandSpec {
+      name: SEARCH_SUBCOMMAND_NAME,
+      about: "Print the lines (of the file, or of `stdin`) that contain the search string.",
+      usage: "[--] <search-string|@alias> [path-to-file-or-dir] [case-sensitive]",
+      flags: &[
+        FlagSpec {
+          long: "--case-sensitive",
+          short: None,
+          value_name: None,
+          help: "Match the case of the search string",
+        },
+        FlagSpec {
+          long: "--after-context",
+          short: Some("-A"),
+          value_name: Some("n"),
+          help: "Print <n> lines after each match",
+        },
+        FlagSpec {
+          long: "--before-context",
+          short: Some("-B"),
+          value_name: Some("n"),
+          help: "Print <n> lines before each match",
+        },
+        FlagSpec {
+          long: "--context",
+          short: Some("-C"),
+          value_name: Some("n"),
+          help: "Print <n> lines before & after each match",
+        },
+        FlagSpec {
+          long: "--grapheme-mode",
+          short: None,
+          value_name: None,
+          help: "Only match whole grapheme clusters",
+        },
+        NFC_FLAG,
+        NFD_FLAG,
+        COLOR_FLAG,
+        FlagSpec {
+          long: "--encoding",
+          short: None,
+          value_name: Some("auto|utf-8|utf-16le|utf-16be|latin1"),
+          help: "How to decode the input (default: auto)",
+        },
+        FlagSpec {
+          long: "--watch",
+          short: None,
+          value_name: None,
+          help: "Re-run the search each time the file (or directory) changes",
+        },
+        FlagSpec {
+          long: "--threads",
+          short: None,
+          value_name: Some("n"),
+          help: "Search a directory w/ <n> worker threads (default: 1 per CPU)",
+        },
+        FlagSpec {
+          long: "--stats",
+          short: None,
+          value_name: None,
+          help: "Print the stats of each worker thread when searching a directory",
+        },
+        FlagSpec {
+          long: "--no-ignore",
+          short: None,
+          value_name: None,
+          help: "Also search .git, target, node_modules, etc. when searching a directory",
+        },
+        FlagSpec {
+          long: "--ignore",
+          short: None,
+          value_name: Some("glob"),
+          help: "Skip the files & directories whose name matches <glob>",
+        },
+      ],
+      subcommands: &[],
+    },
+    CommandSpec {
+      name: GRAPHEME_SUBCOMMAND_NAME,
+      about: "Print each grapheme cluster in the text.",
+      usage: "<text>",
+      flags: &[NFC_FLAG, NFD_FLAG, COLOR_FLAG],
+      subcommands: &[],
+    },
+  ],
+};
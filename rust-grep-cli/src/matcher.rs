@@ -0,0 +1,320 @@
+use std::{borrow::Cow, collections::HashSet};
+
+use serde::Deserialize;
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{cli_args::split_off_positional_args, output::primary};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Normalization {
+  Nfc,
+  Nfd,
+}
+
+/// Options that change how the search string is matched against each line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MatchOptions {
+  /// Only match on grapheme cluster boundaries, so that searching for "🙏" does not match
+  /// the first half of "🙏🏽".
+  pub grapheme_mode: bool,
+  /// Applied to both the search string & each line before matching (the original line is
+  /// printed).
+  pub normalization: Option<Normalization>,
+}
+
+impl MatchOptions {
+  /// Removes the match flags from `args` (before the positional args) & returns the options
+  /// they hold, so the rest of the args can be parsed positionally. These flags are
+  /// supported:
+  /// - `--grapheme-mode`
+  /// - `--nfc` or `--nfd` (but not both)
+  pub fn extract_from(args: &mut Vec<String>) -> Result<MatchOptions, String> {
+    let mut options = MatchOptions::default();
+    let mut error: Option<String> = None;
+
+    let positional_args = split_off_positional_args(args);
+    args.retain(|arg| {
+      let normalization = match arg.as_str() {
+        "--grapheme-mode" => {
+          options.grapheme_mode = true;
+          return false;
+        }
+        "--nfc" => Normalization::Nfc,
+        "--nfd" => Normalization::Nfd,
+        _ => return true,
+      };
+      if matches!(options.normalization, Some(it) if it != normalization) {
+        error = Some("Only one of '--nfc' and '--nfd' can be used.".to_string());
+      }
+      options.normalization = Some(normalization);
+      false
+    });
+    args.extend(positional_args);
+
+    match error {
+      Some(error) => Err(error),
+      None => Ok(options),
+    }
+  }
+}
+
+/// Matches (& highlights) the search string in each line, using [MatchOptions].
+pub struct Matcher {
+  search: String,
+  case_sensitive: bool,
+  options: MatchOptions,
+}
+
+impl Matcher {
+  pub fn new(
+    search: &str,
+    case_sensitive: bool,
+    options: MatchOptions,
+  ) -> Self {
+    let search = normalize(search, options.normalization).into_owned();
+    Self {
+      search,
+      case_sensitive,
+      options,
+    }
+  }
+
+  /// The line is normalized (the same way as the search string) before it is matched.
+  pub fn is_match(
+    &self,
+    line: &str,
+  ) -> bool {
+    let line = normalize(line, self.options.normalization);
+    match self.case_sensitive {
+      true => !self.find(&line, &self.search).is_empty(),
+      false => !self
+        .find(&line.to_lowercase(), &self.search.to_lowercase())
+        .is_empty(),
+    }
+  }
+
+  /// Colorizes the occurrences of the search string (w/ the same case) in the line. The
+  /// line itself isn't normalized, see [Matcher::find_normalized].
+  pub fn highlight(
+    &self,
+    line: &str,
+  ) -> String {
+    let ranges = match self.options.normalization {
+      None => self.find(line, &self.search),
+      Some(normalization) => self.find_normalized(line, normalization),
+    };
+    let mut highlighted = String::new();
+    let mut last_end = 0;
+    for (start, end) in ranges {
+      highlighted.push_str(&line[last_end..start]);
+      highlighted.push_str(&primary(&line[start..end]));
+      last_end = end;
+    }
+    highlighted.push_str(&line[last_end..]);
+    highlighted
+  }
+
+  /// Returns the byte ranges (in `line`) of the occurrences of the search string in the
+  /// normalized `line`. Each grapheme cluster is normalized on its own, so that the ranges
+  /// can be mapped back to `line`. An occurrence that starts or ends inside of a cluster
+  /// covers the whole cluster.
+  fn find_normalized(
+    &self,
+    line: &str,
+    normalization: Normalization,
+  ) -> Vec<(usize, usize)> {
+    let mut normalized_line = String::new();
+    // The start of each cluster, in `normalized_line` & in `line`.
+    let mut boundaries: Vec<(usize, usize)> = Vec::new();
+    for (start, cluster) in line.grapheme_indices(true) {
+      boundaries.push((normalized_line.len(), start));
+      normalized_line.push_str(&normalize(cluster, Some(normalization)));
+    }
+    boundaries.push((normalized_line.len(), line.len()));
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in self.find(&normalized_line, &self.search) {
+      let start = boundaries[boundaries.partition_point(|(it, _)| *it <= start) - 1].1;
+      let end = boundaries[boundaries.partition_point(|(it, _)| *it < end)].1;
+      match ranges.last_mut() {
+        // Occurrences inside of the same cluster are merged.
+        Some((_, last_end)) if start < *last_end => *last_end = end,
+        _ => ranges.push((start, end)),
+      }
+    }
+    ranges
+  }
+
+  /// Returns the byte ranges of the non overlapping occurrences of `needle` in `haystack`.
+  /// In grapheme mode, an occurrence has to start & end on a grapheme cluster boundary.
+  fn find(
+    &self,
+    haystack: &str,
+    needle: &str,
+  ) -> Vec<(usize, usize)> {
+    if needle.is_empty() {
+      return vec![];
+    }
+
+    if !self.options.grapheme_mode {
+      return haystack
+        .match_indices(needle)
+        .map(|(start, it)| (start, start + it.len()))
+        .collect();
+    }
+
+    let boundaries: HashSet<usize> = haystack
+      .grapheme_indices(true)
+      .map(|(index, _)| index)
+      .chain(std::iter::once(haystack.len()))
+      .collect();
+
+    let mut ranges = Vec::new();
+    let mut last_end = 0;
+    for (start, _) in haystack.grapheme_indices(true) {
+      let end = start + needle.len();
+      if start >= last_end
+        && haystack[start..].starts_with(needle)
+        && boundaries.contains(&end)
+      {
+        ranges.push((start, end));
+        last_end = end;
+      }
+    }
+    ranges
+  }
+}
+
+pub fn normalize(
+  text: &str,
+  normalization: Option<Normalization>,
+) -> Cow<'_, str> {
+  match normalization {
+    None => Cow::Borrowed(text),
+    Some(Normalization::Nfc) => Cow::Owned(text.nfc().collect()),
+    Some(Normalization::Nfd) => Cow::Owned(text.nfd().collect()),
+  }
+}
+
+#[test]
+fn test_grapheme_mode_does_not_match_part_of_a_cluster() {
+  let line = "thanks 🙏🏽";
+
+  let matcher = Matcher::new("🙏", true, MatchOptions::default());
+  assert!(matcher.is_match(line));
+
+  let matcher = Matcher::new(
+    "🙏",
+    true,
+    MatchOptions {
+      grapheme_mode: true,
+      normalization: None,
+    },
+  );
+  assert!(!matcher.is_match(line));
+  assert!(matcher.is_match("thanks 🙏"));
+  assert!(matcher.is_match("🙏🏽🙏"));
+}
+
+#[test]
+fn test_grapheme_mode_case_insensitive() {
+  let matcher = Matcher::new(
+    "E",
+    false,
+    MatchOptions {
+      grapheme_mode: true,
+      normalization: None,
+    },
+  );
+  // "e" followed by a combining acute accent is a single grapheme cluster.
+  assert!(!matcher.is_match("cafe\u{301}"));
+  assert!(matcher.is_match("CAFE"));
+}
+
+#[test]
+fn test_normalization() {
+  let composed = "caf\u{e9}";
+  let decomposed = "cafe\u{301}";
+
+  let matcher = Matcher::new(composed, true, MatchOptions::default());
+  assert!(!matcher.is_match(decomposed));
+
+  for normalization in [Normalization::Nfc, Normalization::Nfd] {
+    let matcher = Matcher::new(
+      composed,
+      true,
+      MatchOptions {
+        grapheme_mode: true,
+        normalization: Some(normalization),
+      },
+    );
+    assert!(matcher.is_match(decomposed));
+    assert!(matcher.is_match(composed));
+  }
+}
+
+#[test]
+fn test_highlight_normalized_match_in_original_line() {
+  let decomposed = "cafe\u{301} au lait";
+  for normalization in [Normalization::Nfc, Normalization::Nfd] {
+    let options = MatchOptions {
+      grapheme_mode: false,
+      normalization: Some(normalization),
+    };
+    let matcher = Matcher::new("caf\u{e9}", true, options);
+    assert_eq!(
+      matcher.highlight(decomposed),
+      format!("{} au lait", primary("cafe\u{301}"))
+    );
+  }
+
+  // The match is only part of the (NFD) "é" cluster, so the whole cluster is highlighted.
+  let matcher = Matcher::new(
+    "e",
+    true,
+    MatchOptions {
+      grapheme_mode: false,
+      normalization: Some(Normalization::Nfd),
+    },
+  );
+  assert_eq!(
+    matcher.highlight("caf\u{e9} e"),
+    format!("caf{} {}", primary("\u{e9}"), primary("e"))
+  );
+}
+
+#[test]
+fn test_highlight_only_grapheme_aligned_matches() {
+  let matcher = Matcher::new(
+    "🙏",
+    true,
+    MatchOptions {
+      grapheme_mode: true,
+      normalization: None,
+    },
+  );
+  let highlighted = matcher.highlight("🙏🏽 🙏");
+  assert_eq!(highlighted, format!("🙏🏽 {}", primary("🙏")));
+}
+
+#[test]
+fn test_extract_match_options_from_args() {
+  let mut args = vec!["program", "--nfd", "search", "--grapheme-mode"]
+    .into_iter()
+    .map(String::from)
+    .collect::<Vec<String>>();
+  let options = MatchOptions::extract_from(&mut args).unwrap();
+  assert_eq!(
+    options,
+    MatchOptions {
+      grapheme_mode: true,
+      normalization: Some(Normalization::Nfd),
+    }
+  );
+  assert_eq!(args, vec!["program", "search"]);
+
+  let mut args = vec!["--nfc".to_string(), "--nfd".to_string()];
+  assert!(MatchOptions::extract_from(&mut args).is_err());
+}
@@ -0,0 +1,173 @@
+use std::{env,
+          io::{self, IsTerminal},
+          sync::atomic::{AtomicU8, Ordering}};
+
+use r3bl_rs_utils::utils::{style_error, style_primary, style_prompt};
+use serde::Deserialize;
+
+use crate::cli_args::split_off_positional_args;
+
+/// When to colorize output. This mirrors the `--color` flag of `grep` & `ls`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+  /// Only if the output stream is a terminal & the `NO_COLOR` env var isn't set.
+  #[default]
+  Auto,
+  Always,
+  Never,
+}
+
+static COLOR_MODE: AtomicU8 = AtomicU8::new(ColorMode::Auto as u8);
+
+impl ColorMode {
+  /// Removes the `--color=<mode>` (or `--color <mode>`) flags from `args` (before the
+  /// positional args) & returns the mode that the last one holds, or `None` if none were
+  /// passed. The mode is one of: `auto`, `always`, `never`.
+  pub fn extract_from(args: &mut Vec<String>) -> Result<Option<ColorMode>, String> {
+    let mut color_mode: Option<ColorMode> = None;
+
+    let positional_args = split_off_positional_args(args);
+    let mut remaining_args = Vec::new();
+    let mut args_iter = args.drain(..);
+    while let Some(arg) = args_iter.next() {
+      let value = match arg.strip_prefix("--color=") {
+        Some(value) => value.to_string(),
+        None if arg == "--color" => args_iter
+          .next()
+          .ok_or_else(|| "Expected auto, always or never after '--color'.".to_string())?,
+        None => {
+          remaining_args.push(arg);
+          continue;
+        }
+      };
+      color_mode = Some(match value.as_str() {
+        "auto" => ColorMode::Auto,
+        "always" => ColorMode::Always,
+        "never" => ColorMode::Never,
+        _ => {
+          return Err(format!(
+            "Expected auto, always or never after '--color', got '{}'.",
+            value
+          ))
+        }
+      });
+    }
+    drop(args_iter);
+    *args = remaining_args;
+    args.extend(positional_args);
+
+    Ok(color_mode)
+  }
+
+  /// Sets the mode used by the functions in this module. Until this is called, the mode is
+  /// [ColorMode::Auto].
+  pub fn set_global(self) { COLOR_MODE.store(self as u8, Ordering::SeqCst); }
+
+  pub fn get_global() -> ColorMode {
+    match COLOR_MODE.load(Ordering::SeqCst) {
+      it if it == ColorMode::Always as u8 => ColorMode::Always,
+      it if it == ColorMode::Never as u8 => ColorMode::Never,
+      _ => ColorMode::Auto,
+    }
+  }
+
+  pub fn should_colorize(
+    self,
+    is_tty: bool,
+    is_no_color_set: bool,
+  ) -> bool {
+    match self {
+      ColorMode::Always => true,
+      ColorMode::Never => false,
+      ColorMode::Auto => is_tty && !is_no_color_set,
+    }
+  }
+}
+
+/// <https://no-color.org/>: `NO_COLOR` only counts if it isn't empty.
+fn is_no_color_set() -> bool {
+  match env::var_os("NO_COLOR") {
+    Some(value) => !value.is_empty(),
+    None => false,
+  }
+}
+
+fn should_colorize(stream: impl IsTerminal) -> bool {
+  ColorMode::get_global().should_colorize(stream.is_terminal(), is_no_color_set())
+}
+
+/// Wraps [style_primary], for text printed to `stdout`.
+pub fn primary(text: &str) -> String {
+  match should_colorize(io::stdout()) {
+    true => style_primary(text).to_string(),
+    false => text.to_string(),
+  }
+}
+
+/// Wraps [style_prompt], for text printed to `stdout`.
+pub fn prompt(text: &str) -> String {
+  match should_colorize(io::stdout()) {
+    true => style_prompt(text).to_string(),
+    false => text.to_string(),
+  }
+}
+
+/// Wraps [style_error], for text printed to `stderr`.
+pub fn error(text: &str) -> String {
+  match should_colorize(io::stderr()) {
+    true => style_error(text).to_string(),
+    false => text.to_string(),
+  }
+}
+
+#[test]
+fn test_should_colorize() {
+  assert!(ColorMode::Always.should_colorize(false, true));
+  assert!(!ColorMode::Never.should_colorize(true, false));
+  assert!(ColorMode::Auto.should_colorize(true, false));
+  assert!(!ColorMode::Auto.should_colorize(false, false));
+  assert!(!ColorMode::Auto.should_colorize(true, true));
+}
+
+#[test]
+fn test_extract_color_mode_from_args() {
+  let mut args = vec!["program".to_string(), "--color=never".to_string()];
+  assert_eq!(
+    ColorMode::extract_from(&mut args),
+    Ok(Some(ColorMode::Never))
+  );
+  assert_eq!(args, vec!["program"]);
+
+  let mut args = vec![
+    "program".to_string(),
+    "--color".to_string(),
+    "always".to_string(),
+    "search".to_string(),
+  ];
+  assert_eq!(
+    ColorMode::extract_from(&mut args),
+    Ok(Some(ColorMode::Always))
+  );
+  assert_eq!(args, vec!["program", "search"]);
+
+  let mut args = vec!["program".to_string()];
+  assert_eq!(ColorMode::extract_from(&mut args), Ok(None));
+
+  // The last one wins, & none of them are left in the args.
+  let mut args = vec![
+    "program".to_string(),
+    "--color=always".to_string(),
+    "--color=never".to_string(),
+    "--".to_string(),
+    "--color=auto".to_string(),
+  ];
+  assert_eq!(
+    ColorMode::extract_from(&mut args),
+    Ok(Some(ColorMode::Never))
+  );
+  assert_eq!(args, vec!["program", "--", "--color=auto"]);
+
+  let mut args = vec!["--color=sometimes".to_string()];
+  assert!(ColorMode::extract_from(&mut args).is_err());
+}
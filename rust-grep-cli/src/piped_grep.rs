@@ -3,38 +3,37 @@ use std::{
   io::{stdin, BufRead},
 };
 
-use r3bl_rs_utils::utils::{style_primary, style_prompt};
-
-use crate::piped_grep_command_builder::PipedGrepOptions;
+use crate::{context::{print_output_line, ContextFilter},
+            encoding::decode_line,
+            matcher::Matcher,
+            output::prompt,
+            piped_grep_command_builder::PipedGrepOptions};
 
 pub fn piped_grep(options: PipedGrepOptions) -> Result<(), Box<dyn Error>> {
   println!(
     "{}: search for '{}' in `stdin` w/ {}",
-    style_prompt("DEBUG"),
+    prompt("DEBUG"),
     options.search,
     match options.case_sensitive {
       true => "case sensitive",
       false => "case insensitive",
     }
   );
-  stdin()
-    .lock()
-    .lines()
-    .filter(|line| {
-      let line = line.as_ref().unwrap();
-      if options.case_sensitive {
-        line.contains(&options.search)
-      } else {
-        line.to_lowercase().contains(&options.search.to_lowercase())
-      }
-    })
-    .map(|line| line.unwrap())
-    .for_each(|line| {
-      let from = &options.search;
-      let to = format!("{}", style_primary(&options.search));
-      let line = line.replace(from, &to);
-      println!("{}", line);
-    });
+  let matcher = Matcher::new(&options.search, options.case_sensitive, options.matching);
+  let mut context_filter = ContextFilter::new(options.context);
+  // Lines are split as bytes, so ones that aren't valid UTF-8 don't end the search.
+  for line in stdin().lock().split(b'\n') {
+    let mut line = line?;
+    if line.last() == Some(&b'\r') {
+      line.pop();
+    }
+    let line = decode_line(&line, options.encoding);
+    let is_match = matcher.is_match(&line);
+    context_filter
+      .push(line, is_match)
+      .into_iter()
+      .for_each(|output_line| print_output_line(output_line, &matcher));
+  }
 
   Ok(())
 }
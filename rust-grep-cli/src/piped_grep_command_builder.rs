@@ -1,7 +1,18 @@
+use crate::{cli_args::{extract_switch, remove_separator},
+            context::ContextOptions,
+            encoding::Encoding,
+            matcher::MatchOptions,
+            pipeline::PipelineOptions,
+            watch::extract_watch_flag};
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PipedGrepOptions {
   pub search: String,
   pub case_sensitive: bool,
+  pub context: ContextOptions,
+  pub matching: MatchOptions,
+  /// `None` to decode lines that aren't valid UTF-8 as Latin-1.
+  pub encoding: Option<Encoding>,
 }
 
 const REQUIRED_ARGS_COUNT: usize = 2;
@@ -9,7 +20,28 @@ const REQUIRED_ARGS_COUNT: usize = 2;
 pub struct PipedGrepOptionsBuilder;
 
 impl PipedGrepOptionsBuilder {
-  pub fn parse(args: Vec<String>) -> Result<PipedGrepOptions, String> {
+  pub fn parse(mut args: Vec<String>) -> Result<PipedGrepOptions, String> {
+    let context = ContextOptions::extract_from(&mut args)?;
+    let matching = MatchOptions::extract_from(&mut args)?;
+    let encoding = Encoding::extract_from(&mut args)?;
+    // The ignore globs can come from the config, so they are allowed (& unused) here.
+    let pipeline = PipelineOptions::extract_from(&mut args)?;
+    if pipeline.threads.is_some() || pipeline.stats || pipeline.no_ignore {
+      return Err(
+        "'--threads', '--stats' & '--no-ignore' only work when searching a directory."
+          .to_string(),
+      );
+    }
+    if extract_watch_flag(&mut args) {
+      return Err("'--watch' only works when searching a file or a directory.".to_string());
+    }
+    // UTF-16 can't be split into lines before it is decoded.
+    if let Some(Encoding::Utf16Le | Encoding::Utf16Be) = encoding {
+      return Err("UTF-16 only works when searching a file or a directory.".to_string());
+    }
+    let case_sensitive = extract_switch(&mut args, "--case-sensitive");
+    remove_separator(&mut args);
+
     if args.len() < REQUIRED_ARGS_COUNT {
       return Err(format!(
         "Expected at least {} arguments, got {}.",
@@ -26,7 +58,11 @@ impl PipedGrepOptionsBuilder {
         Some(arg) => arg.clone(),
         None => String::new(),
       },
-      case_sensitive: args.next().is_some(), // If 3rd arg exists, then true.
+      // The flag, or (for backwards compatibility) any extra positional arg.
+      case_sensitive: case_sensitive || args.next().is_some(),
+      context,
+      matching,
+      encoding,
     };
 
     Ok(options)
@@ -0,0 +1,519 @@
+use std::{collections::BTreeMap,
+          error::Error,
+          ffi::OsStr,
+          fmt,
+          fs::{self, DirEntry},
+          io::{self, Write},
+          num::NonZeroUsize,
+          panic,
+          path::{Path, PathBuf},
+          sync::{mpsc::sync_channel, Arc, Mutex},
+          thread,
+          time::{Duration, Instant}};
+
+use crate::{cli_args::split_off_positional_args,
+            context::{format_output_line, ContextOptions, OutputLine},
+            encoding::{decode, Decoded, Encoding},
+            grep::{search_binary, search_content},
+            matcher::Matcher};
+
+/// How many files can be in flight (walked, but not printed yet) per worker. This bounds
+/// the memory that is used by the results which are waiting for an earlier (slower) file
+/// to be printed first.
+const IN_FLIGHT_FILES_PER_WORKER: usize = 4;
+
+/// Directories that are skipped by default, since they hold version control data, build
+/// output & dependencies, rather than the files that are being searched for.
+const IGNORED_DIR_NAMES: &[&str] = &[".git", ".hg", ".svn", "target", "node_modules"];
+
+/// Options for searching a directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PipelineOptions {
+  /// The number of worker threads, or `None` for 1 per CPU.
+  pub threads: Option<usize>,
+  /// Print the stats of each worker to `stderr` once the search is done.
+  pub stats: bool,
+  /// Also search the directories in [IGNORED_DIR_NAMES].
+  pub no_ignore: bool,
+  /// Globs (see [is_glob_match]) for the names of the files & directories to skip.
+  pub ignore_pattern_vec: Vec<String>,
+}
+
+impl PipelineOptions {
+  /// Removes the pipeline flags from `args` (before the positional args) & returns the
+  /// options they hold, so the rest of the args can be parsed positionally. These flags are
+  /// supported:
+  /// - `--threads <n>`, `--threads=<n>`
+  /// - `--stats`
+  /// - `--no-ignore`
+  /// - `--ignore <glob>`, `--ignore=<glob>` (can be passed more than once)
+  pub fn extract_from(args: &mut Vec<String>) -> Result<PipelineOptions, String> {
+    let mut options = PipelineOptions::default();
+
+    let positional_args = split_off_positional_args(args);
+    let mut remaining_args = Vec::new();
+    let mut args_iter = args.drain(..);
+    while let Some(arg) = args_iter.next() {
+      let value = match arg.as_str() {
+        "--stats" => {
+          options.stats = true;
+          continue;
+        }
+        "--no-ignore" => {
+          options.no_ignore = true;
+          continue;
+        }
+        "--ignore" => {
+          let pattern = args_iter
+            .next()
+            .ok_or_else(|| "Expected a glob after '--ignore'.".to_string())?;
+          options.ignore_pattern_vec.push(pattern);
+          continue;
+        }
+        "--threads" => args_iter
+          .next()
+          .ok_or_else(|| "Expected a number of threads after '--threads'.".to_string())?,
+        _ => {
+          if let Some(pattern) = arg.strip_prefix("--ignore=") {
+            options.ignore_pattern_vec.push(pattern.to_string());
+            continue;
+          }
+          match arg.strip_prefix("--threads=") {
+            Some(value) => value.to_string(),
+            None => {
+              remaining_args.push(arg);
+              continue;
+            }
+          }
+        }
+      };
+      options.threads = match value.parse::<usize>() {
+        Ok(threads) if threads > 0 => Some(threads),
+        _ => {
+          return Err(format!(
+            "Expected a number of threads (> 0) after '--threads', got '{}'.",
+            value
+          ))
+        }
+      };
+    }
+    drop(args_iter);
+    *args = remaining_args;
+    args.extend(positional_args);
+
+    Ok(options)
+  }
+
+  pub fn get_thread_count(&self) -> usize {
+    match self.threads {
+      Some(threads) => threads,
+      None => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+    }
+  }
+
+  /// Whether the file or directory named `name` is skipped when walking a directory.
+  pub fn is_ignored(
+    &self,
+    name: &OsStr,
+    is_dir: bool,
+  ) -> bool {
+    let is_ignored_by_default =
+      is_dir && !self.no_ignore && IGNORED_DIR_NAMES.iter().any(|it| name == *it);
+    is_ignored_by_default
+      || self
+        .ignore_pattern_vec
+        .iter()
+        .any(|pattern| is_glob_match(pattern, &name.to_string_lossy()))
+  }
+}
+
+/// Whether `text` matches the glob `pattern`, where `*` matches any number of chars & `?`
+/// matches a single char.
+fn is_glob_match(
+  pattern: &str,
+  text: &str,
+) -> bool {
+  let pattern: Vec<char> = pattern.chars().collect();
+  let text: Vec<char> = text.chars().collect();
+  let (mut pattern_index, mut text_index) = (0, 0);
+  // The pattern index after the last `*`, & the text index that it matches up to. If the
+  // rest of the pattern doesn't match, then the `*` matches 1 more char & it is retried.
+  let mut star: Option<(usize, usize)> = None;
+  while text_index < text.len() {
+    match pattern.get(pattern_index) {
+      Some('*') => {
+        pattern_index += 1;
+        star = Some((pattern_index, text_index));
+      }
+      Some(&it) if it == '?' || it == text[text_index] => {
+        pattern_index += 1;
+        text_index += 1;
+      }
+      _ => match star {
+        Some((star_pattern_index, star_text_index)) => {
+          pattern_index = star_pattern_index;
+          text_index = star_text_index + 1;
+          star = Some((star_pattern_index, text_index));
+        }
+        None => return false,
+      },
+    }
+  }
+  pattern[pattern_index..].iter().all(|it| *it == '*')
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WorkerStats {
+  pub worker_id: usize,
+  pub files: usize,
+  /// Files that couldn't be read.
+  pub skipped_files: usize,
+  /// Files that were searched as binary, so their lines aren't counted.
+  pub binary_files: usize,
+  pub lines: usize,
+  pub matches: usize,
+  pub bytes: usize,
+  /// Time spent searching (not waiting for work).
+  pub busy: Duration,
+}
+
+impl fmt::Display for WorkerStats {
+  fn fmt(
+    &self,
+    f: &mut fmt::Formatter<'_>,
+  ) -> fmt::Result {
+    write!(
+      f,
+      "worker {}: {} files ({} skipped, {} binary), {} lines, {} matches, {} bytes, busy for {:?}",
+      self.worker_id,
+      self.files,
+      self.skipped_files,
+      self.binary_files,
+      self.lines,
+      self.matches,
+      self.bytes,
+      self.busy
+    )
+  }
+}
+
+struct FileResult {
+  /// The order in which the file was walked.
+  index: usize,
+  output: Vec<String>,
+}
+
+/// Searches every file under `dir` (except the ignored ones, see
+/// [PipelineOptions::is_ignored]) & writes the results to `out`, in the order in which
+/// the files are walked (sorted by name, depth first). So the output is the same no matter
+/// how many threads are used. Each file is decoded using `encoding`, or the one that is
+/// detected for it (see [Encoding::detect]).
+///
+/// 1. A walker thread feeds the file paths into a bounded channel.
+/// 2. Worker threads take the next path (whichever worker is idle gets it), search the
+///    file, & send the formatted results on.
+/// 3. The calling thread reassembles the results in walk order & writes them to `out`.
+///
+/// The walker can only get [IN_FLIGHT_FILES_PER_WORKER] files (per worker) ahead of what
+/// has been written, so memory stays bounded even if one file is slow to search.
+pub fn search_dir(
+  dir: &Path,
+  matcher: &Matcher,
+  context: ContextOptions,
+  encoding: Option<Encoding>,
+  options: &PipelineOptions,
+  out: &mut impl Write,
+) -> Result<Vec<WorkerStats>, Box<dyn Error>> {
+  let thread_count = options.get_thread_count();
+  let in_flight_count = thread_count * IN_FLIGHT_FILES_PER_WORKER;
+
+  thread::scope(|scope| {
+    let (path_sender, path_receiver) = sync_channel::<(usize, PathBuf)>(thread_count);
+    let (result_sender, result_receiver) = sync_channel::<FileResult>(in_flight_count);
+    // The walker sends a permit before each path, & one is taken back after each file is
+    // written, which is what bounds the number of files in flight.
+    let (permit_sender, permit_receiver) = sync_channel::<()>(in_flight_count);
+
+    scope.spawn(move || {
+      let mut index = 0;
+      walk(dir, options, &mut |path| {
+        let is_sent =
+          permit_sender.send(()).is_ok() && path_sender.send((index, path)).is_ok();
+        index += 1;
+        is_sent
+      });
+    });
+
+    let path_receiver = Arc::new(Mutex::new(path_receiver));
+    let worker_handles: Vec<_> = (0..thread_count)
+      .map(|worker_id| {
+        let path_receiver = Arc::clone(&path_receiver);
+        let result_sender = result_sender.clone();
+        scope.spawn(move || {
+          let mut stats = WorkerStats {
+            worker_id,
+            ..WorkerStats::default()
+          };
+          loop {
+            // The lock is only held while waiting for the next path.
+            let next = path_receiver.lock().unwrap().recv();
+            let (index, path) = match next {
+              Ok(it) => it,
+              Err(_) => break, // The walker is done.
+            };
+            let start = Instant::now();
+            let output = search_file(&path, matcher, context, encoding, &mut stats);
+            stats.busy += start.elapsed();
+            if result_sender.send(FileResult { index, output }).is_err() {
+              break; // The output loop has stopped (eg: `out` is closed).
+            }
+          }
+          stats
+        })
+      })
+      .collect();
+    // Only the workers have senders now, so the loop below ends once they are all done.
+    drop(result_sender);
+
+    let mut pending_output_map: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    let mut next_index = 0;
+    let mut has_written = false;
+    for FileResult { index, output } in result_receiver.iter() {
+      pending_output_map.insert(index, output);
+      while let Some(output) = pending_output_map.remove(&next_index) {
+        if context.is_enabled() && has_written && !output.is_empty() {
+          writeln!(out, "--")?;
+        }
+        for line in &output {
+          writeln!(out, "{}", line)?;
+        }
+        has_written |= !output.is_empty();
+        next_index += 1;
+        let _ = permit_receiver.recv();
+      }
+    }
+
+    Ok(
+      worker_handles
+        .into_iter()
+        .map(|handle| {
+          handle
+            .join()
+            .unwrap_or_else(|it| panic::resume_unwind(it))
+        })
+        .collect(),
+    )
+  })
+}
+
+/// Calls `visit` w/ each file under `dir`, sorted by name, depth first. Symlinks aren't
+/// followed (so there are no cycles). Ignored files & directories are skipped, & directories
+/// that can't be read are reported on `stderr` & skipped. Stops early if `visit` returns false.
+fn walk(
+  dir: &Path,
+  options: &PipelineOptions,
+  visit: &mut impl FnMut(PathBuf) -> bool,
+) {
+  let mut dir_stack = vec![dir.to_path_buf()];
+  while let Some(dir) = dir_stack.pop() {
+    let entry_vec_result: io::Result<Vec<DirEntry>> =
+      fs::read_dir(&dir).and_then(|it| it.collect());
+    let mut entry_vec = match entry_vec_result {
+      Ok(it) => it,
+      Err(error) => {
+        eprintln!("{}: {}", dir.display(), error);
+        continue;
+      }
+    };
+    entry_vec.sort_by_key(|it| it.file_name());
+
+    // Sub directories are walked after the files in this directory.
+    let mut sub_dir_vec = Vec::new();
+    for entry in entry_vec {
+      let file_type = match entry.file_type() {
+        Ok(it) => it,
+        Err(_) => continue,
+      };
+      if options.is_ignored(&entry.file_name(), file_type.is_dir()) {
+        continue;
+      }
+      if file_type.is_dir() {
+        sub_dir_vec.push(entry.path());
+      } else if file_type.is_file() && !visit(entry.path()) {
+        return;
+      }
+    }
+    dir_stack.extend(sub_dir_vec.into_iter().rev());
+  }
+}
+
+fn search_file(
+  path: &Path,
+  matcher: &Matcher,
+  context: ContextOptions,
+  encoding: Option<Encoding>,
+  stats: &mut WorkerStats,
+) -> Vec<String> {
+  let bytes = match fs::read(path) {
+    Ok(it) => it,
+    Err(_) => {
+      stats.skipped_files += 1;
+      return vec![];
+    }
+  };
+  stats.files += 1;
+  stats.bytes += bytes.len();
+
+  let output_line_vec = match decode(&bytes, encoding) {
+    Decoded::Text(content) => {
+      stats.lines += content.lines().count();
+      search_content(&content, matcher, context)
+    }
+    Decoded::Binary => {
+      stats.binary_files += 1;
+      search_binary(&bytes, matcher)
+    }
+  };
+
+  let path = path.display().to_string();
+  output_line_vec
+    .into_iter()
+    .inspect(|it| {
+      if let OutputLine::Match(_) | OutputLine::BinaryMatch = it {
+        stats.matches += 1;
+      }
+    })
+    .map(|it| format_output_line(it, matcher, Some(&path)))
+    .collect()
+}
+
+#[test]
+fn test_extract_pipeline_options_from_args() {
+  let mut args = vec![
+    "program",
+    "--threads",
+    "4",
+    "--no-ignore",
+    "--ignore",
+    "*.log",
+    "search",
+    "--stats",
+    "--ignore=vendor",
+    "dir",
+  ]
+  .into_iter()
+  .map(String::from)
+  .collect::<Vec<String>>();
+  let options = PipelineOptions::extract_from(&mut args).unwrap();
+  assert_eq!(
+    options,
+    PipelineOptions {
+      threads: Some(4),
+      stats: true,
+      no_ignore: true,
+      ignore_pattern_vec: vec!["*.log".to_string(), "vendor".to_string()],
+    }
+  );
+  assert_eq!(args, vec!["program", "search", "dir"]);
+
+  let mut args = vec!["--threads=0".to_string()];
+  assert!(PipelineOptions::extract_from(&mut args).is_err());
+}
+
+#[test]
+fn test_is_glob_match() {
+  assert!(is_glob_match("vendor", "vendor"));
+  assert!(!is_glob_match("vendor", "vendors"));
+  assert!(is_glob_match("*.log", "a.log"));
+  assert!(is_glob_match("*.log", ".log"));
+  assert!(!is_glob_match("*.log", "a.log.txt"));
+  assert!(is_glob_match("*.min.*", "app.min.js"));
+  assert!(is_glob_match("a*b*c", "aXbYbZc"));
+  assert!(!is_glob_match("a*b*c", "aXbYbZ"));
+  assert!(is_glob_match("file?.txt", "file1.txt"));
+  assert!(!is_glob_match("file?.txt", "file.txt"));
+  assert!(is_glob_match("*", ""));
+  assert!(is_glob_match("🙏?", "🙏🏽"));
+}
+
+#[test]
+fn test_search_dir_output_is_in_walk_order() {
+  use crate::matcher::MatchOptions;
+
+  let dir = std::env::temp_dir().join(format!("rust-grep-cli-{}", std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  for (file_path, content) in [
+    ("b/d.txt", "match d\n"),
+    ("b/c/e.txt", "no\nmatch e\n"),
+    ("a.txt", "match a\nno\n"),
+    ("c.txt", "no\n"),
+    ("d.txt", "match d 1\nmatch d 2\n"),
+  ] {
+    let file_path = dir.join(file_path);
+    fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+    fs::write(file_path, content).unwrap();
+  }
+  // Binary, so only a single line is printed for it.
+  fs::write(dir.join("binary.dat"), b"\x7fELF\x00\x00match\x00").unwrap();
+  fs::write(dir.join("latin1.txt"), b"caf\xe9 match\n").unwrap();
+  // Ignored.
+  for file_path in [
+    ".git/match.txt",
+    "b/target/match.txt",
+    "b/match.log",
+    "vendor/match.txt",
+  ] {
+    let file_path = dir.join(file_path);
+    fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+    fs::write(file_path, "match\n").unwrap();
+  }
+
+  let matcher = Matcher::new("match", true, MatchOptions::default());
+  let search = |threads: usize| {
+    let mut out: Vec<u8> = Vec::new();
+    let options = PipelineOptions {
+      threads: Some(threads),
+      ignore_pattern_vec: vec!["*.log".to_string(), "vendor".to_string()],
+      ..PipelineOptions::default()
+    };
+    let stats_vec =
+      search_dir(&dir, &matcher, ContextOptions::default(), None, &options, &mut out)
+        .unwrap();
+    (String::from_utf8(out).unwrap(), stats_vec)
+  };
+
+  let (output, stats_vec) = search(1);
+  let path_vec: Vec<&str> = output
+    .lines()
+    .map(|line| line.split(':').next().unwrap())
+    .collect();
+  let expected_path_vec: Vec<String> = [
+    "a.txt",
+    "binary.dat",
+    "d.txt",
+    "d.txt",
+    "latin1.txt",
+    "b/d.txt",
+    "b/c/e.txt",
+  ]
+  .iter()
+  .map(|it| dir.join(it).display().to_string())
+  .collect();
+  assert_eq!(path_vec, expected_path_vec);
+  assert!(output.contains("binary.dat: (binary file matched)\n"));
+  assert!(output.contains("café"));
+  assert_eq!(stats_vec.len(), 1);
+  assert_eq!(stats_vec[0].files, 7);
+  assert_eq!(stats_vec[0].skipped_files, 0);
+  assert_eq!(stats_vec[0].binary_files, 1);
+  assert_eq!(stats_vec[0].matches, 7);
+
+  for threads in [2, 8] {
+    let (threaded_output, stats_vec) = search(threads);
+    assert_eq!(threaded_output, output);
+    assert_eq!(stats_vec.len(), threads);
+    assert_eq!(stats_vec.iter().map(|it| it.matches).sum::<usize>(), 7);
+  }
+
+  fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,221 @@
+use std::{collections::BTreeSet,
+          error::Error,
+          path::{Path, PathBuf},
+          sync::mpsc::{channel, Receiver, Sender},
+          time::Duration};
+
+use notify::{Config, Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{cli_args::extract_switch, output::prompt};
+
+/// How often files are checked when the OS can't tell us that they changed.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Saving a file often takes a few writes (eg: truncate, write, rename), so changes are
+/// collected until there haven't been any for this long, then the search is run once.
+const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Removes the `--watch` flag from `args` (before the positional args) & returns whether it
+/// was there.
+pub fn extract_watch_flag(args: &mut Vec<String>) -> bool { extract_switch(args, "--watch") }
+
+/// Calls `search` once, then again each time a file under `path` (or `path` itself, if it
+/// is a file) changes. Before each re-run, the paths that changed since the last run are
+/// printed. If `search` fails (eg: the file was removed), the error is reported on `stderr`
+/// & the watch goes on. This only returns if the watcher fails.
+pub fn watch(
+  path: &Path,
+  mut search: impl FnMut() -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+  // The paths of the events are absolute, so they can only be compared w/ a canonical path.
+  let path = path.canonicalize()?;
+  let (sender, receiver) = channel();
+  // The watcher stops when it is dropped.
+  let _watcher = start_watcher(&path, sender)?;
+
+  run_search(&mut search);
+  loop {
+    let changed_path_set = wait_for_changes(&receiver, &path)?;
+    println!(
+      "{}: changed since last run: {}",
+      prompt("WATCH"),
+      changed_path_set
+        .iter()
+        .map(|it| it.display().to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+    );
+    run_search(&mut search);
+  }
+}
+
+fn run_search(search: &mut impl FnMut() -> Result<(), Box<dyn Error>>) {
+  if let Err(error) = search() {
+    eprintln!("{}: search failed: {}", prompt("WATCH"), error);
+  }
+}
+
+/// Uses the OS's file events (eg: `inotify`) if it can, otherwise falls back to polling.
+///
+/// A file is watched via its parent directory. Editors often save a file by writing a temp
+/// file & renaming it over the original one, & a watch on the file itself would stop at the
+/// 1st save (since it follows the original inode, which is gone).
+fn start_watcher(
+  path: &Path,
+  sender: Sender<notify::Result<Event>>,
+) -> notify::Result<Box<dyn Watcher>> {
+  let (path, recursive_mode) = match path.parent() {
+    Some(parent) if path.is_file() => (parent, RecursiveMode::NonRecursive),
+    _ => (path, RecursiveMode::Recursive),
+  };
+  let recommended_watcher_result = RecommendedWatcher::new(sender.clone(), Config::default())
+    .and_then(|mut watcher| {
+      watcher.watch(path, recursive_mode)?;
+      Ok(watcher)
+    });
+  match recommended_watcher_result {
+    Ok(watcher) => Ok(Box::new(watcher)),
+    Err(error) => {
+      eprintln!("{}: {}, polling for changes instead", prompt("WATCH"), error);
+      let mut watcher =
+        PollWatcher::new(sender, Config::default().with_poll_interval(POLL_INTERVAL))?;
+      watcher.watch(path, recursive_mode)?;
+      Ok(Box::new(watcher))
+    }
+  }
+}
+
+/// Blocks until at least 1 path under `path` (or `path` itself, if it is a file) has
+/// changed (& then until [DEBOUNCE_INTERVAL] passes w/out any more changes), & returns the
+/// paths that changed. Changes to other paths (eg: the other files in the directory of a
+/// file that is watched) are skipped. Errors from the watcher are reported on `stderr` &
+/// otherwise ignored.
+fn wait_for_changes(
+  receiver: &Receiver<notify::Result<Event>>,
+  path: &Path,
+) -> Result<BTreeSet<PathBuf>, Box<dyn Error>> {
+  let mut changed_path_set = BTreeSet::new();
+  let mut next_event = receiver.recv()?;
+  loop {
+    match next_event {
+      Ok(event) if is_change(&event.kind) => changed_path_set.extend(
+        event
+          .paths
+          .into_iter()
+          .filter(|it| it.starts_with(path)),
+      ),
+      Ok(_) => {}
+      Err(error) => eprintln!("{}: {}", prompt("WATCH"), error),
+    }
+    next_event = match receiver.recv_timeout(DEBOUNCE_INTERVAL) {
+      Ok(it) => it,
+      Err(_) if !changed_path_set.is_empty() => return Ok(changed_path_set),
+      Err(_) => receiver.recv()?,
+    };
+  }
+}
+
+/// Reading a file (eg: when it is searched) isn't a change.
+fn is_change(kind: &EventKind) -> bool {
+  matches!(
+    kind,
+    EventKind::Any | EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+  )
+}
+
+#[test]
+fn test_extract_watch_flag_from_args() {
+  let mut args = vec!["program", "--watch", "search", "dir"]
+    .into_iter()
+    .map(String::from)
+    .collect::<Vec<String>>();
+  assert!(extract_watch_flag(&mut args));
+  assert_eq!(args, vec!["program", "search", "dir"]);
+  assert!(!extract_watch_flag(&mut args));
+}
+
+#[test]
+fn test_wait_for_changes_in_dir() {
+  use std::fs;
+
+  let dir = std::env::temp_dir().join(format!("rust-grep-cli-watch-{}", std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  let dir = dir.canonicalize().unwrap();
+
+  let (sender, receiver) = channel();
+  let watcher = start_watcher(&dir, sender).unwrap();
+  fs::write(dir.join("a.txt"), "match\n").unwrap();
+  let changed_path_set = wait_for_changes(&receiver, &dir).unwrap();
+  assert!(changed_path_set.contains(&dir.join("a.txt")));
+
+  drop(watcher);
+  fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_wait_for_changes_to_file_saved_by_renaming() {
+  use std::{fs, thread};
+
+  let dir =
+    std::env::temp_dir().join(format!("rust-grep-cli-watch-file-{}", std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  let dir = dir.canonicalize().unwrap();
+  let file_path = dir.join("a.txt");
+  fs::write(&file_path, "match 0\n").unwrap();
+
+  let (sender, receiver) = channel();
+  let watcher = start_watcher(&file_path, sender).unwrap();
+  // `wait_for_changes` blocks until there is a change, so it runs on another thread.
+  let (changed_sender, changed_receiver) = channel();
+  let watched_file_path = file_path.clone();
+  thread::spawn(move || {
+    while let Ok(changed_path_set) = wait_for_changes(&receiver, &watched_file_path) {
+      if changed_sender.send(changed_path_set).is_err() {
+        break;
+      }
+    }
+  });
+
+  // Each save is done the way editors do it, & only changes to the file are reported.
+  for index in 1..=2 {
+    fs::write(dir.join("other.txt"), "match\n").unwrap();
+    fs::write(dir.join("a.txt.tmp"), format!("match {}\n", index)).unwrap();
+    fs::rename(dir.join("a.txt.tmp"), &file_path).unwrap();
+    let changed_path_set = changed_receiver
+      .recv_timeout(Duration::from_secs(5))
+      .unwrap();
+    assert_eq!(changed_path_set, BTreeSet::from([file_path.clone()]));
+  }
+
+  drop(watcher);
+  fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_watch_keeps_going_after_search_fails() {
+  use std::{fs, thread};
+
+  let dir =
+    std::env::temp_dir().join(format!("rust-grep-cli-watch-fail-{}", std::process::id()));
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  let dir = dir.canonicalize().unwrap();
+
+  let (call_sender, call_receiver) = channel();
+  let watched_dir = dir.clone();
+  // `watch` doesn't return, so this thread is left running.
+  thread::spawn(move || {
+    let _ = watch(&watched_dir, || {
+      let _ = call_sender.send(());
+      Err("search failed".into())
+    });
+  });
+  let timeout = Duration::from_secs(5);
+  call_receiver.recv_timeout(timeout).unwrap();
+  fs::write(dir.join("a.txt"), "match\n").unwrap();
+  call_receiver.recv_timeout(timeout).unwrap();
+
+  fs::remove_dir_all(&dir).unwrap();
+}
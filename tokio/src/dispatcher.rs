@@ -0,0 +1,184 @@
+/*
+ Copyright 2022 Nazmul Idris
+
+ Licensed under the Apache License, Version 2.0 (the "License");
+ you may not use this file except in compliance with the License.
+ You may obtain a copy of the License at
+
+      https://www.apache.org/licenses/LICENSE-2.0
+
+ Unless required by applicable law or agreed to in writing, software
+ distributed under the License is distributed on an "AS IS" BASIS,
+ WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+// Imports.
+use std::ops::ControlFlow;
+
+use tokio::{
+  sync::mpsc::{error::SendError, unbounded_channel, UnboundedReceiver, UnboundedSender},
+  task::JoinHandle,
+  time::{interval_at, sleep, Duration, Instant},
+};
+
+/// Cheap to clone handle that can be moved into any task (or thread) to dispatch actions
+/// into a [Dispatcher].
+pub struct Dispatch<A> {
+  sender: UnboundedSender<A>,
+}
+
+impl<A> Clone for Dispatch<A> {
+  fn clone(&self) -> Self {
+    Self {
+      sender: self.sender.clone(),
+    }
+  }
+}
+
+impl<A> Dispatch<A> {
+  /// Queues the action. It is delivered after all the actions that have already been
+  /// dispatched. Returns an error (w/ the action) if the dispatcher has stopped running.
+  pub fn dispatch(
+    &self,
+    action: A,
+  ) -> Result<(), SendError<A>> {
+    self.sender.send(action)
+  }
+}
+
+impl<A: Send + 'static> Dispatch<A> {
+  /// Dispatches the action once `duration` has passed (eg: to hide a toast after 3s). The
+  /// action goes through the middleware like any other. Must be called from inside a tokio
+  /// runtime.
+  pub fn set_timeout(
+    &self,
+    duration: Duration,
+    action: A,
+  ) -> TimerHandle {
+    let dispatch = self.clone();
+    TimerHandle {
+      join_handle: tokio::spawn(async move {
+        sleep(duration).await;
+        let _ = dispatch.dispatch(action);
+      }),
+    }
+  }
+
+  /// Dispatches a clone of the action every `period`, starting one `period` from now. It
+  /// stops on its own once the dispatcher has stopped running. Must be called from inside a
+  /// tokio runtime.
+  pub fn set_interval(
+    &self,
+    period: Duration,
+    action: A,
+  ) -> TimerHandle
+  where
+    A: Clone,
+  {
+    let dispatch = self.clone();
+    TimerHandle {
+      join_handle: tokio::spawn(async move {
+        let mut interval = interval_at(Instant::now() + period, period);
+        loop {
+          interval.tick().await;
+          if dispatch.dispatch(action.clone()).is_err() {
+            break;
+          }
+        }
+      }),
+    }
+  }
+}
+
+/// Returned by [Dispatch::set_timeout] & [Dispatch::set_interval]. Dropping it does *not*
+/// cancel the timer, call [TimerHandle::cancel] to do that.
+#[derive(Debug)]
+pub struct TimerHandle {
+  join_handle: JoinHandle<()>,
+}
+
+impl TimerHandle {
+  /// An action that has already been dispatched (but not delivered yet) is still
+  /// delivered.
+  pub fn cancel(&self) { self.join_handle.abort(); }
+}
+
+/// A middleware function gets every action before `update` does. It returns:
+/// 1. `Some(action)` to pass the action (possibly changed) on to the next middleware.
+/// 2. `None` to drop the action (eg: debouncing).
+///
+/// Side effects (eg: network or filesystem work) can be spawned from a middleware, using a
+/// clone of the [Dispatch] to send follow up actions when they are done.
+pub type MiddlewareFn<A> = Box<dyn FnMut(A, &Dispatch<A>) -> Option<A> + Send + Sync>;
+
+/// Async action dispatcher, w/ a middleware chain in front of the `update` function.
+///
+/// Actions are delivered to `update` one at a time, in the order in which they were
+/// dispatched. Follow up actions dispatched from middleware (or from tasks they spawn) are
+/// queued behind the actions that are already waiting.
+pub struct Dispatcher<A> {
+  dispatch: Dispatch<A>,
+  receiver: UnboundedReceiver<A>,
+  middleware_vec: Vec<MiddlewareFn<A>>,
+}
+
+impl<A: Send + 'static> Default for Dispatcher<A> {
+  fn default() -> Self { Self::new() }
+}
+
+impl<A: Send + 'static> Dispatcher<A> {
+  pub fn new() -> Self {
+    let (sender, receiver) = unbounded_channel();
+    Self {
+      dispatch: Dispatch { sender },
+      receiver,
+      middleware_vec: Vec::new(),
+    }
+  }
+
+  /// Get a handle that can be used to dispatch actions from any task.
+  pub fn get_dispatch(&self) -> Dispatch<A> {
+    self.dispatch.clone()
+  }
+
+  /// Middleware runs in the order in which it is added.
+  pub fn add_middleware(
+    &mut self,
+    middleware_fn: impl FnMut(A, &Dispatch<A>) -> Option<A> + Send + Sync + 'static,
+  ) -> &mut Self {
+    self.middleware_vec.push(Box::new(middleware_fn));
+    self
+  }
+
+  /// Runs until `update` returns [ControlFlow::Break], and then returns the final state.
+  /// Actions that are still queued at that point are dropped.
+  pub async fn run<S>(
+    mut self,
+    mut state: S,
+    mut update: impl FnMut(&mut S, A) -> ControlFlow<()>,
+  ) -> S {
+    // `self.dispatch` keeps the channel open, so `recv()` only returns `None` if there's a
+    // bug, in which case there is nothing left to deliver anyway.
+    while let Some(action) = self.receiver.recv().await {
+      if let Some(action) = self.apply_middleware(action) {
+        if update(&mut state, action).is_break() {
+          break;
+        }
+      }
+    }
+    state
+  }
+
+  fn apply_middleware(
+    &mut self,
+    action: A,
+  ) -> Option<A> {
+    let dispatch = &self.dispatch;
+    self
+      .middleware_vec
+      .iter_mut()
+      .try_fold(action, |action, middleware_fn| middleware_fn(action, dispatch))
+  }
+}
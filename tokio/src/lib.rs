@@ -15,5 +15,6 @@
 */
 
 // Connect to source files.
+pub mod dispatcher;
 pub mod middleware;
 pub mod my_middleware;
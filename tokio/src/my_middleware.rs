@@ -14,7 +14,11 @@
  limitations under the License.
 */
 
-use crate::middleware::SafeFnWrapper;
+use std::fmt::Debug;
+
+use tokio::time::{Duration, Instant};
+
+use crate::{dispatcher::Dispatch, middleware::SafeFnWrapper};
 
 /// Does not capture context or return anything.
 pub fn logger_mw() -> SafeFnWrapper<Action> {
@@ -39,6 +43,48 @@ pub fn adder_mw() -> SafeFnWrapper<Action> {
   SafeFnWrapper::new(adder_lambda)
 }
 
+/// Dispatcher middleware that logs every action & passes it on unchanged.
+pub fn dispatch_logger_mw<A: Debug>() -> impl FnMut(A, &Dispatch<A>) -> Option<A> {
+  |action: A, _: &Dispatch<A>| {
+    println!("dispatching: {:?}", action);
+    Some(action)
+  }
+}
+
+/// Dispatcher middleware that drops an action if it is the same as the previous action
+/// that was passed on, and arrives less than `window` after it.
+pub fn debounce_mw<A: PartialEq + Clone>(
+  window: Duration
+) -> impl FnMut(A, &Dispatch<A>) -> Option<A> {
+  let mut last: Option<(A, Instant)> = None;
+  move |action: A, _: &Dispatch<A>| {
+    let now = Instant::now();
+    if let Some((last_action, last_instant)) = &last {
+      if *last_action == action && now.duration_since(*last_instant) < window {
+        return None;
+      }
+    }
+    last = Some((action.clone(), now));
+    Some(action)
+  }
+}
+
+/// Dispatcher middleware that runs a side effect. For every `Add` it spawns a task to do
+/// the addition, which then dispatches the `Result` as a follow up action. The `Add` itself
+/// is passed on unchanged.
+pub fn async_adder_mw() -> impl FnMut(Action, &Dispatch<Action>) -> Option<Action> {
+  |action: Action, dispatch: &Dispatch<Action>| {
+    if let Action::Add(a, b) = action {
+      let dispatch = dispatch.clone();
+      tokio::spawn(async move {
+        // The dispatcher may have stopped by the time this is done, that's ok.
+        let _ = dispatch.dispatch(Action::Result(a + b));
+      });
+    }
+    Some(action)
+  }
+}
+
 /// Action enum.
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub enum Action {
@@ -0,0 +1,147 @@
+/*
+ Copyright 2022 Nazmul Idris
+
+ Licensed under the Apache License, Version 2.0 (the "License");
+ you may not use this file except in compliance with the License.
+ You may obtain a copy of the License at
+
+      https://www.apache.org/licenses/LICENSE-2.0
+
+ Unless required by applicable law or agreed to in writing, software
+ distributed under the License is distributed on an "AS IS" BASIS,
+ WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ See the License for the specific language governing permissions and
+ limitations under the License.
+*/
+
+use std::ops::ControlFlow;
+
+use tokio::time::Duration;
+use tokio_example_lib::{
+  dispatcher::Dispatcher,
+  my_middleware::{async_adder_mw, debounce_mw, dispatch_logger_mw, Action},
+};
+
+/// Stops the dispatcher once the `Result(stop_at)` action is delivered.
+fn record_until(stop_at: i32) -> impl FnMut(&mut Vec<Action>, Action) -> ControlFlow<()> {
+  move |state: &mut Vec<Action>, action: Action| {
+    let is_done = action == Action::Result(stop_at);
+    state.push(action);
+    match is_done {
+      true => ControlFlow::Break(()),
+      false => ControlFlow::Continue(()),
+    }
+  }
+}
+
+#[tokio::test]
+async fn test_dispatcher_delivers_in_order() {
+  let dispatcher = Dispatcher::<Action>::new();
+  let dispatch = dispatcher.get_dispatch();
+
+  // Dispatch from another task.
+  tokio::spawn(async move {
+    for it in 1..=3 {
+      dispatch.dispatch(Action::Result(it)).unwrap();
+    }
+  });
+
+  let state = dispatcher.run(vec![], record_until(3)).await;
+  assert_eq!(
+    state,
+    vec![Action::Result(1), Action::Result(2), Action::Result(3)]
+  );
+}
+
+#[tokio::test]
+async fn test_dispatcher_middleware_can_transform_and_drop() {
+  let mut dispatcher = Dispatcher::<Action>::new();
+  dispatcher
+    .add_middleware(dispatch_logger_mw())
+    // Drop all the `Add` actions.
+    .add_middleware(|action, _| match action {
+      Action::Add(..) => None,
+      _ => Some(action),
+    })
+    // Double all the `Result` actions.
+    .add_middleware(|action, _| match action {
+      Action::Result(it) => Some(Action::Result(it * 2)),
+      _ => Some(action),
+    });
+
+  let dispatch = dispatcher.get_dispatch();
+  dispatch.dispatch(Action::Add(1, 2)).unwrap();
+  dispatch.dispatch(Action::Result(1)).unwrap();
+  dispatch.dispatch(Action::Result(2)).unwrap();
+
+  let state = dispatcher.run(vec![], record_until(4)).await;
+  assert_eq!(state, vec![Action::Result(2), Action::Result(4)]);
+}
+
+#[tokio::test]
+async fn test_dispatcher_side_effect_dispatches_follow_up() {
+  let mut dispatcher = Dispatcher::<Action>::new();
+  dispatcher.add_middleware(async_adder_mw());
+
+  dispatcher.get_dispatch().dispatch(Action::Add(1, 2)).unwrap();
+
+  let state = dispatcher.run(vec![], record_until(3)).await;
+  assert_eq!(state, vec![Action::Add(1, 2), Action::Result(3)]);
+}
+
+#[tokio::test]
+async fn test_dispatcher_debounce_mw() {
+  let mut dispatcher = Dispatcher::<Action>::new();
+  dispatcher.add_middleware(debounce_mw(Duration::from_secs(60)));
+
+  let dispatch = dispatcher.get_dispatch();
+  dispatch.dispatch(Action::Add(1, 2)).unwrap();
+  dispatch.dispatch(Action::Add(1, 2)).unwrap();
+  dispatch.dispatch(Action::Add(2, 2)).unwrap();
+  dispatch.dispatch(Action::Add(1, 2)).unwrap();
+  dispatch.dispatch(Action::Result(0)).unwrap();
+
+  let state = dispatcher.run(vec![], record_until(0)).await;
+  assert_eq!(
+    state,
+    vec![
+      Action::Add(1, 2),
+      Action::Add(2, 2),
+      Action::Add(1, 2),
+      Action::Result(0),
+    ]
+  );
+}
+
+#[tokio::test]
+async fn test_dispatch_set_timeout() {
+  let dispatcher = Dispatcher::<Action>::new();
+  let dispatch = dispatcher.get_dispatch();
+
+  let canceled = dispatch.set_timeout(Duration::from_millis(10), Action::Result(0));
+  canceled.cancel();
+  dispatch.set_timeout(Duration::from_millis(20), Action::Result(2));
+  dispatch.dispatch(Action::Result(1)).unwrap();
+
+  let state = dispatcher.run(vec![], record_until(2)).await;
+  assert_eq!(state, vec![Action::Result(1), Action::Result(2)]);
+}
+
+#[tokio::test]
+async fn test_dispatch_set_interval() {
+  let dispatcher = Dispatcher::<Action>::new();
+  let dispatch = dispatcher.get_dispatch();
+
+  dispatch.set_interval(Duration::from_millis(5), Action::Add(1, 1));
+
+  let state = dispatcher
+    .run(vec![], |state: &mut Vec<Action>, action| {
+      state.push(action);
+      match state.len() {
+        3 => ControlFlow::Break(()),
+        _ => ControlFlow::Continue(()),
+      }
+    })
+    .await;
+  assert_eq!(state, vec![Action::Add(1, 1); 3]);
+}